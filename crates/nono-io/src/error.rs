@@ -2,6 +2,8 @@ use std::io;
 
 use thiserror::Error;
 
+use crate::{NonError, TextError, XmlError};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("{0}")]
@@ -19,8 +21,20 @@ pub enum Error {
     #[error("Image error: {0}")]
     Img(#[from] image::ImageError),
 
+    #[error("Text error on line {line}: {error}")]
+    Text { line: usize, error: TextError },
+
+    #[error(".non error on line {line}: {error}")]
+    Non { line: usize, error: NonError },
+
+    #[error("XML error: {0}")]
+    Xml(#[from] XmlError),
+
     #[error("Tried to parse nonogram from file with unsupported extension '{0}'")]
     UnsupportedExtension(String),
+
+    #[error("Could not detect the format of the nonogram from its contents")]
+    UnknownFormat,
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,452 @@
+mod scale;
+
+pub use scale::*;
+
+use std::{collections::HashMap, io::Cursor, path::Path};
+
+use image::{DynamicImage, ImageFormat, ImageReader, Rgb, RgbImage, Rgba};
+use nono::{Color, Fill, Metadata, Nonogram, Position, Puzzle, Rules};
+
+use crate::{Error, PuzzleLoader, PuzzleWriter, Result, solve_rules};
+
+/// How the pixels of an image are turned into cells
+#[derive(Debug, Clone)]
+pub struct ImageOptions {
+    /// Maximum number of colors, similar colors are merged with median cut if there are more
+    pub max_colors: Option<usize>,
+
+    /// Color of the pixels that are left blank
+    pub background: Color,
+
+    /// Largest difference per channel to the background for pixels to still be blank
+    pub tolerance: u8,
+
+    /// Pixels that are less opaque than this are blank
+    pub alpha_threshold: u8,
+
+    /// Whether pure black pixels are colored cells, otherwise they are blank like the background
+    /// Images with black cells, like the ones [`ImageWriter`] writes for black colors, need this
+    pub black_is_ink: bool,
+
+    /// Size of the puzzle, where [`None`] turns every pixel into a cell
+    pub size: Option<ImageSize>,
+
+    /// How the pixels of a cell are combined into its color
+    pub pooling: Pooling,
+
+    /// Whether to spread the difference to the reduced colors over the neighbouring cells
+    /// Only has an effect together with [`ImageOptions::max_colors`]
+    pub dither: bool,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            max_colors: None,
+            background: (255, 255, 255),
+            tolerance: 0,
+            alpha_threshold: 128,
+            black_is_ink: false,
+            size: None,
+            pooling: Pooling::Majority,
+            dither: false,
+        }
+    }
+}
+
+impl ImageOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_colors(mut self, colors: usize) -> Self {
+        self.max_colors = Some(colors);
+        self
+    }
+
+    pub fn background(mut self, color: Color, tolerance: u8) -> Self {
+        self.background = color;
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn alpha_threshold(mut self, threshold: u8) -> Self {
+        self.alpha_threshold = threshold;
+        self
+    }
+
+    pub fn black_is_ink(mut self, black_is_ink: bool) -> Self {
+        self.black_is_ink = black_is_ink;
+        self
+    }
+
+    pub fn size(mut self, rows: u16, cols: u16) -> Self {
+        self.size = Some(ImageSize::Cells { rows, cols });
+        self
+    }
+
+    pub fn cell_size(mut self, pixels: u32) -> Self {
+        self.size = Some(ImageSize::CellSize(pixels));
+        self
+    }
+
+    pub fn pooling(mut self, pooling: Pooling) -> Self {
+        self.pooling = pooling;
+        self
+    }
+
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Whether a pixel is left blank instead of being a colored cell
+    fn is_blank(&self, pixel: Rgba<u8>) -> bool {
+        let [r, g, b, a] = pixel.0;
+
+        if a < self.alpha_threshold {
+            return true;
+        }
+
+        if (r, g, b) == (0, 0, 0) {
+            return !self.black_is_ink;
+        }
+
+        let (br, bg, bb) = self.background;
+        [r.abs_diff(br), g.abs_diff(bg), b.abs_diff(bb)]
+            .into_iter()
+            .all(|diff| diff <= self.tolerance)
+    }
+}
+
+pub struct ImageLoader;
+
+impl PuzzleLoader for ImageLoader {
+    fn load_nonogram(path: &Path) -> Result<Nonogram> {
+        Self::load_with(path, &ImageOptions::default())
+    }
+}
+
+impl ImageLoader {
+    /// Load a nonogram from an image, scaled down to the size of the options
+    ///
+    /// * `path`: Path of the image
+    /// * `options`: How the pixels are turned into cells
+    pub fn load_with(path: &Path, options: &ImageOptions) -> Result<Nonogram> {
+        let image = ImageReader::open(path)?.decode()?;
+        Self::from_image(&image, options)
+    }
+
+    /// Load a nonogram from encoded image data, like a PNG that is read from stdin
+    ///
+    /// * `bytes`: Encoded image, whose format is guessed from its contents
+    /// * `options`: How the pixels are turned into cells
+    pub fn load_from_memory(bytes: &[u8], options: &ImageOptions) -> Result<Nonogram> {
+        let image = image::load_from_memory(bytes)?;
+        Self::from_image(&image, options)
+    }
+
+    fn from_image(image: &DynamicImage, options: &ImageOptions) -> Result<Nonogram> {
+        let (picture, colors) = read_picture(image, options)?;
+        let puzzle = Puzzle::empty(picture.rows(), picture.cols());
+
+        let nonogram = Nonogram {
+            puzzle,
+            rules: Rules::from_puzzle(&picture),
+            colors,
+            metadata: Metadata::default(),
+        };
+
+        nonogram.check()?;
+        Ok(nonogram)
+    }
+}
+
+pub struct ImageWriter;
+
+impl PuzzleWriter for ImageWriter {
+    fn write_nonogram(path: &Path, nonogram: &Nonogram) -> Result<()> {
+        Self::to_image(nonogram)?.save(path)?;
+
+        Ok(())
+    }
+}
+
+impl ImageWriter {
+    /// Draw the unique solution with a single pixel per cell, leaving blank cells white
+    pub fn to_image(nonogram: &Nonogram) -> Result<RgbImage> {
+        let solution = solve_rules(nonogram)?;
+        let (rows, cols) = (solution.rows(), solution.cols());
+
+        let mut image = RgbImage::new(cols as u32, rows as u32);
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let (r, g, b) = match solution[Position::new(row, col)] {
+                    Fill::Color(id) => *id
+                        .checked_sub(1)
+                        .and_then(|idx| nonogram.colors.get(idx as usize))
+                        .ok_or_else(|| Error::Custom(format!("Color {id} is not defined")))?,
+                    _ => (255, 255, 255),
+                };
+
+                image.put_pixel(col as u32, row as u32, Rgb([r, g, b]));
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Encode the unique solution as a PNG file
+    pub fn to_png(nonogram: &Nonogram) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        Self::to_image(nonogram)?.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+
+        Ok(bytes)
+    }
+}
+
+/// Turn the pixels of the image into a picture, numbering the colors in the order they appear
+///
+/// * `image`: Image to read the cells from
+/// * `options`: How the pixels are turned into cells
+fn read_picture(image: &DynamicImage, options: &ImageOptions) -> Result<(Puzzle, Vec<Color>)> {
+    let (rows, cols) = grid_size(image, options)?;
+    let cells = pool_cells(image, options, rows, cols);
+
+    // Merge similar colors if there are too many
+    let cells = match options.max_colors {
+        Some(max_colors) => {
+            let palette = quantize(cells.iter().flatten().copied(), max_colors);
+
+            match options.dither {
+                true => dither(&cells, &palette, options, cols),
+                false => cells
+                    .into_iter()
+                    .map(|cell| cell.map(|color| palette.get(&color).copied().unwrap_or(color)))
+                    .collect(),
+            }
+        }
+        None => cells,
+    };
+
+    let mut colors = Vec::new();
+    let fills = cells
+        .into_iter()
+        .map(|cell| {
+            let Some(color) = cell else {
+                return Fill::Blank;
+            };
+
+            let idx = match colors.iter().position(|&col| col == color) {
+                Some(idx) => idx + 1,
+                None => {
+                    colors.push(color);
+                    colors.len()
+                }
+            };
+
+            Fill::Color(idx as u16)
+        })
+        .collect();
+
+    let picture = Puzzle::new(rows, cols, fills)?;
+    Ok((picture, colors))
+}
+
+/// Colors of a bucket of the median cut, with the number of pixels of each color
+type Bucket = Vec<(Color, usize)>;
+
+/// Reduce the colors to at most `max_colors` with median cut
+/// Returns the color of the palette that each original color is replaced by
+///
+/// * `colors`: Colors of all pixels
+/// * `max_colors`: Maximum number of colors of the palette
+fn quantize(colors: impl Iterator<Item = Color>, max_colors: usize) -> HashMap<Color, Color> {
+    let mut counts: HashMap<Color, usize> = HashMap::new();
+    for color in colors {
+        *counts.entry(color).or_default() += 1;
+    }
+
+    if counts.len() <= max_colors.max(1) {
+        return HashMap::new();
+    }
+
+    // Sort the colors so the buckets do not depend on the order of the map
+    let mut colors: Bucket = counts.into_iter().collect();
+    colors.sort();
+
+    let mut buckets: Vec<Bucket> = vec![colors];
+
+    while buckets.len() < max_colors {
+        // Split the bucket with the widest range on any channel
+        let Some((idx, channel, _)) = buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, bucket)| bucket.len() > 1)
+            .map(|(idx, bucket)| {
+                let (channel, range) = widest_channel(bucket);
+                (idx, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range)
+        else {
+            break;
+        };
+
+        let mut bucket = buckets.swap_remove(idx);
+        bucket.sort_by_key(|&(color, _)| (channel_of(color, channel), color));
+
+        // Split in between two different values of the channel, as close to the median pixel as
+        // possible
+        let total: usize = bucket.iter().map(|(_, count)| count).sum();
+        let mut seen = 0;
+        let mut split = 1;
+        let mut best = usize::MAX;
+
+        for idx in 1..bucket.len() {
+            seen += bucket[idx - 1].1;
+
+            let (prev, curr) = (bucket[idx - 1].0, bucket[idx].0);
+            if channel_of(prev, channel) == channel_of(curr, channel) {
+                continue;
+            }
+
+            let distance = (seen * 2).abs_diff(total);
+            if distance < best {
+                best = distance;
+                split = idx;
+            }
+        }
+
+        let upper = bucket.split_off(split);
+        buckets.push(bucket);
+        buckets.push(upper);
+    }
+
+    let mut palette = HashMap::new();
+
+    for bucket in buckets {
+        let average = average_color(&bucket);
+
+        for (color, _) in bucket {
+            palette.insert(color, average);
+        }
+    }
+
+    palette
+}
+
+fn channel_of((r, g, b): Color, channel: usize) -> u8 {
+    [r, g, b][channel]
+}
+
+/// Channel with the largest difference between the colors of the bucket, and that difference
+fn widest_channel(bucket: &Bucket) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let values = bucket.iter().map(|&(color, _)| channel_of(color, channel));
+            let min = values.clone().min().unwrap_or(0);
+            let max = values.max().unwrap_or(0);
+
+            (channel, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+/// Average of the colors of the bucket, weighted by their number of pixels
+fn average_color(bucket: &Bucket) -> Color {
+    let total: usize = bucket.iter().map(|(_, count)| count).sum();
+    let channel = |channel: usize| {
+        let sum: usize = bucket
+            .iter()
+            .map(|&(color, count)| channel_of(color, channel) as usize * count)
+            .sum();
+
+        ((sum + total / 2) / total.max(1)) as u8
+    };
+
+    (channel(0), channel(1), channel(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+    use rstest::rstest;
+
+    const B: Fill = Fill::Blank;
+    const C1: Fill = Fill::Color(1);
+    const C2: Fill = Fill::Color(2);
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const GREY: [u8; 4] = [240, 240, 240, 255];
+    const CLEAR: [u8; 4] = [200, 0, 0, 0];
+    const RED: [u8; 4] = [200, 0, 0, 255];
+    const DARK_RED: [u8; 4] = [190, 10, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 200, 255];
+
+    fn image(pixels: &[[u8; 4]]) -> DynamicImage {
+        let mut image = RgbaImage::new(pixels.len() as u32, 1);
+        for (x, &pixel) in pixels.iter().enumerate() {
+            image.put_pixel(x as u32, 0, Rgba(pixel));
+        }
+
+        DynamicImage::ImageRgba8(image)
+    }
+
+    #[rstest]
+    #[case::default(ImageOptions::new(), &[BLACK, WHITE, RED, CLEAR], &[B, B, C1, B], 1)]
+    #[case::black_is_ink(ImageOptions::new().black_is_ink(true), &[BLACK, WHITE, RED], &[C1, B, C2], 2)]
+    #[case::tolerance(ImageOptions::new().background((255, 255, 255), 20), &[GREY, RED], &[B, C1], 1)]
+    #[case::background(ImageOptions::new().background((200, 0, 0), 0), &[RED, WHITE, BLUE], &[B, C1, C2], 2)]
+    #[case::opaque(ImageOptions::new().alpha_threshold(0), &[CLEAR, RED], &[C1, C1], 1)]
+    #[case::quantize(ImageOptions::new().max_colors(2), &[RED, DARK_RED, BLUE], &[C1, C1, C2], 2)]
+    #[case::few_colors(ImageOptions::new().max_colors(3), &[RED, DARK_RED, BLUE], &[C1, C2, Fill::Color(3)], 3)]
+    fn read_options(
+        #[case] options: ImageOptions,
+        #[case] pixels: &[[u8; 4]],
+        #[case] expected: &[Fill],
+        #[case] colors: usize,
+    ) {
+        let (picture, found) = read_picture(&image(pixels), &options).unwrap();
+
+        assert!(picture.iter_cells().eq(expected));
+        assert_eq!(found.len(), colors);
+    }
+
+    #[test]
+    fn round_trip_black() {
+        let picture = Puzzle::new(2, 3, vec![C1, C1, C1, B, C1, B]).unwrap();
+        let expected = Nonogram {
+            puzzle: Puzzle::empty(2, 3),
+            rules: Rules::from_puzzle(&picture),
+            colors: vec![(0, 0, 0)],
+            metadata: Metadata::default(),
+        };
+
+        let name = format!("nono-io-{}-black.png", std::process::id());
+        let path = std::env::temp_dir().join(name);
+
+        // Black pixels are blank by default, so the cells are only read back when asked to
+        ImageWriter::write_nonogram(&path, &expected).unwrap();
+        let found = ImageLoader::load_with(&path, &ImageOptions::new().black_is_ink(true));
+        std::fs::remove_file(&path).unwrap();
+
+        let found = found.unwrap();
+        assert_eq!(found.rules.rows, expected.rules.rows);
+        assert_eq!(found.rules.cols, expected.rules.cols);
+        assert_eq!(found.colors, expected.colors);
+    }
+
+    #[test]
+    fn quantize_average() {
+        let colors = [(200, 0, 0), (190, 10, 0), (190, 10, 0), (0, 0, 200)];
+        let palette = quantize(colors.into_iter(), 2);
+
+        assert_eq!(palette[&(200, 0, 0)], (193, 7, 0));
+        assert_eq!(palette[&(190, 10, 0)], (193, 7, 0));
+        assert_eq!(palette[&(0, 0, 200)], (0, 0, 200));
+    }
+}
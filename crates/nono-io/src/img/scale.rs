@@ -0,0 +1,278 @@
+use std::{collections::HashMap, ops::Range};
+
+use image::{DynamicImage, GenericImageView, Rgba};
+use nono::Color;
+
+use crate::{Error, ImageOptions, Result};
+
+/// Size of a puzzle that is read from an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSize {
+    /// Scale the image to the given number of rows and columns
+    Cells { rows: u16, cols: u16 },
+
+    /// Turn each square of the given number of pixels into a single cell
+    CellSize(u32),
+}
+
+/// Way to combine the pixels of a cell into its color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Pooling {
+    /// Average all pixels, which suits photos and smooth gradients
+    Average,
+
+    /// Take the most common color, which keeps the sharp edges of pixel art
+    #[default]
+    Majority,
+}
+
+/// Number of rows and columns of the puzzle, which are never more than the pixels of the image
+///
+/// * `image`: Image to read the cells from
+/// * `options`: Options with the size of the puzzle
+pub(crate) fn grid_size(image: &DynamicImage, options: &ImageOptions) -> Result<(u16, u16)> {
+    let (width, height) = image.dimensions();
+
+    let (rows, cols) = match options.size {
+        None => (height, width),
+        Some(ImageSize::Cells { rows, cols }) => {
+            (u32::from(rows).min(height), u32::from(cols).min(width))
+        }
+        Some(ImageSize::CellSize(pixels)) => {
+            let pixels = pixels.max(1);
+            (height.div_ceil(pixels), width.div_ceil(pixels))
+        }
+    };
+
+    if rows == 0 || cols == 0 || rows > u32::from(u16::MAX) || cols > u32::from(u16::MAX) {
+        return Err(Error::Custom(format!(
+            "Cannot read a {rows}x{cols} puzzle from a {width}x{height} image"
+        )));
+    }
+
+    Ok((rows as u16, cols as u16))
+}
+
+/// Combine the pixels of each cell into a single color, where blank cells are [`None`]
+///
+/// * `image`: Image to read the cells from
+/// * `options`: How the pixels are combined
+/// * `rows`: Number of rows of the puzzle
+/// * `cols`: Number of columns of the puzzle
+pub(crate) fn pool_cells(
+    image: &DynamicImage,
+    options: &ImageOptions,
+    rows: u16,
+    cols: u16,
+) -> Vec<Option<Color>> {
+    let (width, height) = image.dimensions();
+    let mut cells = Vec::with_capacity(rows as usize * cols as usize);
+
+    for row in 0..rows as u32 {
+        let ys = block(row, rows as u32, height);
+
+        for col in 0..cols as u32 {
+            let xs = block(col, cols as u32, width);
+
+            let pixels = ys
+                .clone()
+                .flat_map(|y| xs.clone().map(move |x| image.get_pixel(x, y)));
+
+            let cell = match options.pooling {
+                Pooling::Average => classify(average_pixel(pixels), options),
+                Pooling::Majority => majority(pixels.map(|pixel| classify(pixel, options))),
+            };
+
+            cells.push(cell);
+        }
+    }
+
+    cells
+}
+
+/// Replace the colors of the cells by the closest reduced color, spreading the difference over
+/// the cells to the right and below with Floyd-Steinberg dithering
+/// The background is one of the reduced colors, so cells may turn blank, while blank cells stay
+/// blank
+///
+/// * `cells`: Colors of the cells, where blank cells are [`None`]
+/// * `palette`: Reduced color of each original color
+/// * `options`: Options with the background color
+/// * `cols`: Number of columns of the puzzle
+pub(crate) fn dither(
+    cells: &[Option<Color>],
+    palette: &HashMap<Color, Color>,
+    options: &ImageOptions,
+    cols: u16,
+) -> Vec<Option<Color>> {
+    let mut targets: Vec<Color> = palette.values().copied().collect();
+    if targets.is_empty() {
+        return cells.to_vec();
+    }
+
+    targets.sort();
+    targets.dedup();
+    targets.push(options.background);
+
+    let cols = cols as usize;
+    let mut errors = vec![[0.0f32; 3]; cells.len()];
+    let mut dithered = Vec::with_capacity(cells.len());
+
+    for (idx, cell) in cells.iter().enumerate() {
+        let Some((r, g, b)) = *cell else {
+            dithered.push(None);
+            continue;
+        };
+
+        let channels = |(r, g, b): Color| [r, g, b].map(f32::from);
+        let value: [f32; 3] = std::array::from_fn(|c| channels((r, g, b))[c] + errors[idx][c]);
+
+        let distance = |&color: &Color| -> f32 {
+            let target = channels(color);
+            (0..3).map(|c| (target[c] - value[c]).powi(2)).sum()
+        };
+
+        let closest = *targets
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .expect("Palette should not be empty");
+
+        let target = channels(closest);
+        let error: [f32; 3] = std::array::from_fn(|c| value[c] - target[c]);
+
+        // Spread the error to the neighbours that are not visited yet
+        let (row, col) = (idx / cols, idx % cols);
+        let rows = cells.len() / cols;
+
+        let neighbours = [
+            (row, col + 1, 7.0),
+            (row + 1, col.wrapping_sub(1), 3.0),
+            (row + 1, col, 5.0),
+            (row + 1, col + 1, 1.0),
+        ];
+
+        for (row, col, weight) in neighbours {
+            if row >= rows || col >= cols {
+                continue;
+            }
+
+            for (acc, error) in errors[row * cols + col].iter_mut().zip(&error) {
+                *acc += error * weight / 16.0;
+            }
+        }
+
+        dithered.push((closest != options.background).then_some(closest));
+    }
+
+    dithered
+}
+
+/// Pixels that belong to the cell at the given index
+fn block(idx: u32, cells: u32, pixels: u32) -> Range<u32> {
+    let start = idx * pixels / cells;
+    let end = ((idx + 1) * pixels / cells).max(start + 1).min(pixels);
+
+    start..end
+}
+
+fn classify(pixel: Rgba<u8>, options: &ImageOptions) -> Option<Color> {
+    let [r, g, b, _] = pixel.0;
+    (!options.is_blank(pixel)).then_some((r, g, b))
+}
+
+fn average_pixel(pixels: impl Iterator<Item = Rgba<u8>>) -> Rgba<u8> {
+    let mut sums = [0u64; 4];
+    let mut count = 0u64;
+
+    for pixel in pixels {
+        for (sum, channel) in sums.iter_mut().zip(pixel.0) {
+            *sum += u64::from(channel);
+        }
+        count += 1;
+    }
+
+    Rgba(sums.map(|sum| ((sum + count / 2) / count.max(1)) as u8))
+}
+
+/// Most common color of the pixels, preferring the color that appears first on ties
+fn majority(cells: impl Iterator<Item = Option<Color>>) -> Option<Color> {
+    let mut counts: Vec<(Option<Color>, usize)> = Vec::new();
+
+    for cell in cells {
+        match counts.iter_mut().find(|(color, _)| *color == cell) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((cell, 1)),
+        }
+    }
+
+    let mut best: Option<(Option<Color>, usize)> = None;
+    for (color, count) in counts {
+        if best.is_none_or(|(_, best)| count > best) {
+            best = Some((color, count));
+        }
+    }
+
+    best.and_then(|(color, _)| color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+    use rstest::rstest;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const RED: Rgba<u8> = Rgba([200, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 200, 255]);
+
+    fn image(width: u32, pixels: &[Rgba<u8>]) -> DynamicImage {
+        let height = pixels.len() as u32 / width;
+        let image = RgbaImage::from_fn(width, height, |x, y| pixels[(y * width + x) as usize]);
+
+        DynamicImage::ImageRgba8(image)
+    }
+
+    #[rstest]
+    #[case::pixels(None, (4, 6))]
+    #[case::cells(Some(ImageSize::Cells { rows: 2, cols: 3 }), (2, 3))]
+    #[case::larger(Some(ImageSize::Cells { rows: 10, cols: 10 }), (4, 6))]
+    #[case::cell_size(Some(ImageSize::CellSize(4)), (1, 2))]
+    fn grid(#[case] size: Option<ImageSize>, #[case] expected: (u16, u16)) {
+        let options = ImageOptions {
+            size,
+            ..ImageOptions::default()
+        };
+
+        let found = grid_size(&image(6, &[WHITE; 24]), &options).unwrap();
+        assert_eq!(found, expected);
+    }
+
+    #[rstest]
+    #[case::majority(Pooling::Majority, vec![Some((200, 0, 0)), None])]
+    #[case::average(Pooling::Average, vec![Some((214, 64, 64)), Some((191, 191, 241))])]
+    fn pool(#[case] pooling: Pooling, #[case] expected: Vec<Option<Color>>) {
+        #[rustfmt::skip]
+        let pixels = [
+            RED, RED, WHITE, WHITE,
+            RED, WHITE, WHITE, BLUE,
+        ];
+
+        let options = ImageOptions::new().size(1, 2).pooling(pooling);
+        let image = image(4, &pixels);
+
+        assert_eq!(pool_cells(&image, &options, 1, 2), expected);
+    }
+
+    #[test]
+    fn dither_grey() {
+        let grey = (128, 128, 128);
+        let palette = HashMap::from([(grey, (0, 0, 0))]);
+
+        let cells = vec![Some(grey); 8];
+        let dithered = dither(&cells, &palette, &ImageOptions::default(), 8);
+        let ink = dithered.iter().flatten().count();
+
+        assert!((3..=5).contains(&ink));
+        assert!(dithered.iter().flatten().all(|&color| color == (0, 0, 0)));
+    }
+}
@@ -1,38 +1,104 @@
 use std::path::Path;
 
-use nono::{Fill, Nonogram, Puzzle, Rule, Rules, Run};
-use serde::Deserialize;
+use nono::{Fill, Metadata, Nonogram, Position, Puzzle, PuzzleError, Rule, Rules, Run};
+use serde::{Deserialize, Serialize};
 
-use crate::{PuzzleLoader, error::Result};
+use crate::{PuzzleLoader, PuzzleWriter, error::Result};
 
 pub struct JsonLoader;
 
 impl PuzzleLoader for JsonLoader {
     fn load_nonogram(path: &Path) -> Result<Nonogram> {
         let text = std::fs::read_to_string(path)?;
-        let raw: JsonNonogram = serde_json::from_str(&text)?;
-        let nonogram = raw.try_into()?;
+        parse_json(&text)
+    }
+}
 
-        Ok(nonogram)
+/// Parse a nonogram from the contents of a JSON file
+///
+/// * `text`: Contents of the file
+pub fn parse_json(text: &str) -> Result<Nonogram> {
+    let raw: JsonNonogram = serde_json::from_str(text)?;
+    let nonogram = raw.try_into()?;
+
+    Ok(nonogram)
+}
+
+pub struct JsonWriter;
+
+impl PuzzleWriter for JsonWriter {
+    fn write_nonogram(path: &Path, nonogram: &Nonogram) -> Result<()> {
+        std::fs::write(path, Self::to_string(nonogram)?)?;
+
+        Ok(())
+    }
+}
+
+impl JsonWriter {
+    pub fn to_string(nonogram: &Nonogram) -> Result<String> {
+        let raw = JsonNonogram::from(nonogram);
+        let text = serde_json::to_string_pretty(&raw)?;
+
+        Ok(text)
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// Version of the JSON format that is written
+///
+/// * 1: Cells of the puzzle only hold color ids, files without a version are of this format
+/// * 2: Cells of the puzzle can be blank or crossed out, with givens and candidates
+pub const JSON_VERSION: u32 = 2;
+
+/// Nonogram as it is stored in JSON files
+/// Cells of the puzzle are either `null` for blank, 0 for crossed out or the id of their color
+/// In version 1 files cells only hold color ids, where 0 is not a color and is read as blank
+#[derive(Debug, Serialize, Deserialize)]
 pub struct JsonNonogram {
+    #[serde(default = "legacy_version")]
+    pub version: u32,
+
     pub colors: Vec<(u8, u8, u8)>,
     pub rows: Vec<Vec<RawRun>>,
     pub cols: Vec<Vec<RawRun>>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub puzzle: Vec<Vec<Option<u16>>>,
+
+    /// Cells that are filled in from the start and cannot be changed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub givens: Vec<RawGiven>,
+
+    /// Fills that are noted as possible for cells that are not filled in yet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<RawCandidates>,
+
+    /// Title, author and copyright of the nonogram
     #[serde(default)]
-    pub puzzle: Vec<Vec<u16>>,
+    pub metadata: Metadata,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RawRun {
     pub fill: u16,
     pub count: u16,
 }
 
+/// Given cell, where a fill of 0 crosses the cell out
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawGiven {
+    pub row: u16,
+    pub col: u16,
+    pub fill: u16,
+}
+
+/// Candidates of a cell, where a fill of 0 is a cross
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawCandidates {
+    pub row: u16,
+    pub col: u16,
+    pub fills: Vec<u16>,
+}
+
 impl TryFrom<JsonNonogram> for Nonogram {
     type Error = nono::Error;
 
@@ -41,7 +107,10 @@ impl TryFrom<JsonNonogram> for Nonogram {
             .puzzle
             .iter()
             .flatten()
-            .map(|id| Fill::Color(*id))
+            .map(|&id| match data.version {
+                1 => legacy_fill(id),
+                _ => Fill::from(id),
+            })
             .collect();
 
         let rows = data.rows.len() as u16;
@@ -74,16 +143,176 @@ impl TryFrom<JsonNonogram> for Nonogram {
             .collect();
 
         let rules = Rules::new(row_rules, col_rules);
-        let puzzle = if data.puzzle.is_empty() {
+        let mut puzzle = if data.puzzle.is_empty() {
             Puzzle::empty(rows, cols)
         } else {
             Puzzle::new(rows, cols, fills)?
         };
 
-        Ok(Nonogram {
+        for given in &data.givens {
+            let pos = check_bounds(given.row, given.col, rows, cols)?;
+            puzzle.set_given(pos, Fill::from(Some(given.fill)));
+        }
+
+        for candidates in &data.candidates {
+            let pos = check_bounds(candidates.row, candidates.col, rows, cols)?;
+
+            for &id in &candidates.fills {
+                puzzle.add_candidate(pos, Fill::from(Some(id)));
+            }
+        }
+
+        let nonogram = Nonogram {
             puzzle,
             rules,
             colors: data.colors,
-        })
+            metadata: data.metadata,
+        };
+
+        nonogram.check()?;
+        Ok(nonogram)
+    }
+}
+
+impl From<&Nonogram> for JsonNonogram {
+    fn from(nonogram: &Nonogram) -> Self {
+        let puzzle = &nonogram.puzzle;
+        let raw_rules = |rules: &[Rule]| -> Vec<Vec<RawRun>> {
+            rules
+                .iter()
+                .map(|rule| {
+                    rule.runs()
+                        .iter()
+                        .map(|run| RawRun {
+                            fill: raw_fill(run.fill),
+                            count: run.count,
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        let positions = (0..puzzle.rows())
+            .flat_map(|row| (0..puzzle.cols()).map(move |col| Position::new(row, col)));
+
+        // Givens are already part of the loaded puzzle, so the cells are only stored if the
+        // player filled in any other cell
+        let is_started = positions
+            .clone()
+            .any(|pos| puzzle[pos] != Fill::Blank && !puzzle.is_locked(pos));
+
+        let cells = match is_started {
+            true => (0..puzzle.rows())
+                .map(|row| {
+                    (0..puzzle.cols())
+                        .map(|col| puzzle[Position::new(row, col)].into())
+                        .collect()
+                })
+                .collect(),
+            false => Vec::new(),
+        };
+
+        let givens = puzzle
+            .iter_locked()
+            .filter(|&pos| puzzle[pos] != Fill::Blank)
+            .map(|pos| RawGiven {
+                row: pos.row,
+                col: pos.col,
+                fill: raw_fill(puzzle[pos]),
+            })
+            .collect();
+
+        let candidates = puzzle
+            .iter_candidates()
+            .map(|(pos, mask)| RawCandidates {
+                row: pos.row,
+                col: pos.col,
+                fills: mask.iter_fills().map(raw_fill).collect(),
+            })
+            .collect();
+
+        Self {
+            version: JSON_VERSION,
+            colors: nonogram.colors.clone(),
+            rows: raw_rules(&nonogram.rules.rows),
+            cols: raw_rules(&nonogram.rules.cols),
+            puzzle: cells,
+            givens,
+            candidates,
+            metadata: nonogram.metadata.clone(),
+        }
+    }
+}
+
+fn legacy_version() -> u32 {
+    1
+}
+
+/// Fill of a cell in a version 1 file, which could only hold color ids
+fn legacy_fill(id: Option<u16>) -> Fill {
+    match id {
+        None | Some(0) => Fill::Blank,
+        Some(id) => Fill::Color(id),
+    }
+}
+
+/// Id of a fill that is not blank, where crosses are 0
+fn raw_fill(fill: Fill) -> u16 {
+    Option::<u16>::from(fill).unwrap_or(0)
+}
+
+fn check_bounds(row: u16, col: u16, rows: u16, cols: u16) -> nono::Result<Position> {
+    let pos = Position::new(row, col);
+
+    if row >= rows || col >= cols {
+        let err = PuzzleError::OutOfBounds { pos, rows, cols };
+        return Err(nono::Error::Puzzle(err));
+    }
+
+    Ok(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const B: Fill = Fill::Blank;
+    const X: Fill = Fill::Cross;
+    const C1: Fill = Fill::Color(1);
+
+    const RULES: &str = r#""colors": [[0, 0, 0]],
+        "rows": [[{"fill": 1, "count": 1}], []],
+        "cols": [[{"fill": 1, "count": 1}], []]"#;
+
+    #[rstest]
+    #[case::legacy(format!(r#"{{{RULES}, "puzzle": [[1, 0], [0, 0]]}}"#), [C1, B, B, B])]
+    #[case::current(format!(r#"{{"version": 2, {RULES}, "puzzle": [[1, 0], [null, null]]}}"#), [C1, X, B, B])]
+    fn parse_versions(#[case] text: String, #[case] expected: [Fill; 4]) {
+        let nonogram = parse_json(&text).unwrap();
+        assert!(nonogram.puzzle.iter_cells().eq(&expected));
+    }
+
+    #[test]
+    fn write_version() {
+        let nonogram = parse_json(&format!("{{{RULES}}}")).unwrap();
+        let raw = JsonNonogram::from(&nonogram);
+
+        assert_eq!(raw.version, JSON_VERSION);
+    }
+
+    #[test]
+    fn round_trip_metadata() {
+        let mut expected = parse_json(&format!("{{{RULES}}}")).unwrap();
+        expected.metadata = Metadata {
+            title: Some("Dot".to_string()),
+            author: Some("Someone".to_string()),
+            copyright: None,
+        };
+
+        let text = JsonWriter::to_string(&expected).unwrap();
+        let found = parse_json(&text).unwrap();
+
+        assert_eq!(found.metadata, expected.metadata);
     }
 }
@@ -1,17 +1,32 @@
-use nono::Nonogram;
-use std::path::Path;
+use nono::{Nonogram, Puzzle, SolveOptions, SolveOutcome, Solver, SolverError, Uniqueness};
+use std::{io::Read, path::Path, time::Duration};
 
 mod error;
 mod img;
 mod json;
+mod non;
 mod text;
+mod xml;
 
 pub use error::*;
+pub use img::{ImageLoader, ImageOptions, ImageSize, ImageWriter, Pooling};
+pub use json::{JsonNonogram, JsonWriter, RawCandidates, RawGiven, RawRun, parse_json};
+pub use non::{NonError, parse_non, write_non};
+pub use text::{TextError, parse_nonogram, write_nonogram};
+pub use xml::{XmlError, parse_xml, write_xml};
 
 pub trait PuzzleLoader {
     fn load_nonogram(path: &Path) -> Result<Nonogram>;
 }
 
+pub trait PuzzleWriter {
+    fn write_nonogram(path: &Path, nonogram: &Nonogram) -> Result<()>;
+}
+
+/// Load a nonogram in the format of its extension
+/// Files without a known extension, like temporary files, are loaded by their contents instead
+///
+/// * `path`: Path of the file
 pub fn load_nonogram(path: impl AsRef<Path>) -> Result<Nonogram> {
     let path = path.as_ref();
     let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
@@ -20,6 +35,220 @@ pub fn load_nonogram(path: impl AsRef<Path>) -> Result<Nonogram> {
         "json" => json::JsonLoader::load_nonogram(path),
         "png" | "jpg" | "jpeg" => img::ImageLoader::load_nonogram(path),
         "txt" | "text" => text::TextLoader::load_nonogram(path),
+        "non" => non::NonLoader::load_nonogram(path),
+        "xml" | "pbn" => xml::XmlLoader::load_nonogram(path),
+        _ => match load_nonogram_from_reader(std::fs::File::open(path)?) {
+            Err(Error::UnknownFormat) => Err(Error::UnsupportedExtension(ext.to_string())),
+            result => result,
+        },
+    }
+}
+
+/// Load a nonogram from a reader like stdin, detecting its format from the contents
+/// Fails with [`Error::UnknownFormat`] if the contents match none of the formats
+///
+/// * `reader`: Reader with the contents of a puzzle file
+pub fn load_nonogram_from_reader(mut reader: impl Read) -> Result<Nonogram> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.starts_with(PNG_MAGIC) || bytes.starts_with(JPEG_MAGIC) {
+        return img::ImageLoader::load_from_memory(&bytes, &ImageOptions::default());
+    }
+
+    let text = std::str::from_utf8(&bytes).map_err(|_| Error::UnknownFormat)?;
+    let text = text.trim_start_matches('\u{feff}');
+
+    match detect_format(text).ok_or(Error::UnknownFormat)? {
+        Format::Json => parse_json(text),
+        Format::Xml => parse_xml(text),
+        Format::Text => parse_nonogram(text),
+        Format::Non => parse_non(text),
+    }
+}
+
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_MAGIC: &[u8] = &[0xff, 0xd8, 0xff];
+
+/// Keywords that start the lines of a `.non` file
+const NON_KEYWORDS: &[&str] = &[
+    "width",
+    "height",
+    "rows",
+    "columns",
+    "goal",
+    "title",
+    "by",
+    "author",
+    "copyright",
+    "catalogue",
+    "license",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Xml,
+    Text,
+    Non,
+}
+
+/// Guess the format of a text file from its first line with content
+fn detect_format(text: &str) -> Option<Format> {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+
+    let keyword = line.split_whitespace().next().unwrap_or_default();
+
+    match line.chars().next()? {
+        '{' => Some(Format::Json),
+        '<' => Some(Format::Xml),
+        '[' => Some(Format::Text),
+        _ if NON_KEYWORDS.contains(&keyword) => Some(Format::Non),
+        _ => None,
+    }
+}
+
+/// Time the solver may take to find the solution that is written along with the rules
+const SOLVE_TIME_LIMIT: Duration = Duration::from_secs(1);
+
+/// Solve the rules from scratch, so cells that were filled in wrongly are not written
+/// Fails if the rules cannot be solved in time or do not have exactly one solution
+pub(crate) fn solve_rules(nonogram: &Nonogram) -> Result<Puzzle> {
+    let mut solution = Puzzle::empty(nonogram.puzzle.rows(), nonogram.puzzle.cols());
+
+    let mut solver = Solver::new();
+    solver.insert_rules(&nonogram.rules);
+
+    let options = SolveOptions::new().time_limit(SOLVE_TIME_LIMIT);
+    let err = match solver.solve_with(&mut solution, &options) {
+        SolveOutcome::Solved => match solver.count_solutions(&nonogram.rules, 2)? {
+            Uniqueness::Unique(solution) => return Ok(solution),
+            _ => SolverError::NotUnique,
+        },
+        SolveOutcome::BudgetExhausted => SolverError::BudgetExhausted,
+        SolveOutcome::Cancelled => SolverError::Cancelled,
+        SolveOutcome::Stuck(_) | SolveOutcome::Contradiction => SolverError::NoSolution,
+    };
+
+    Err(nono::Error::Solver(err).into())
+}
+
+pub fn save_nonogram(path: impl AsRef<Path>, nonogram: &Nonogram) -> Result<()> {
+    let path = path.as_ref();
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+
+    match ext {
+        "json" => json::JsonWriter::write_nonogram(path, nonogram),
+        "png" => img::ImageWriter::write_nonogram(path, nonogram),
+        "txt" | "text" => text::TextWriter::write_nonogram(path, nonogram),
+        "non" => non::NonWriter::write_nonogram(path, nonogram),
+        "xml" | "pbn" => xml::XmlWriter::write_nonogram(path, nonogram),
         _ => Err(Error::UnsupportedExtension(ext.to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nono::{Fill, Metadata, Puzzle, Rules};
+    use rstest::rstest;
+    use std::io::Cursor;
+
+    const B: Fill = Fill::Blank;
+    const C1: Fill = Fill::Color(1);
+    const C2: Fill = Fill::Color(2);
+
+    /// Uniquely solvable picture whose colors first appear in order, like images are read
+    fn nonogram() -> Nonogram {
+        let picture = Puzzle::new(3, 3, vec![C1, C1, B, B, C2, C2, C1, B, C2]).unwrap();
+
+        Nonogram {
+            puzzle: Puzzle::empty(3, 3),
+            rules: Rules::from_puzzle(&picture),
+            colors: vec![(200, 40, 40), (40, 40, 200)],
+            metadata: Metadata::default(),
+        }
+    }
+
+    /// Temporary file of a test case, so tests running in parallel do not share files
+    fn temp_path(test: &str, ext: &str) -> std::path::PathBuf {
+        let name = format!("nono-io-{}-{test}.{ext}", std::process::id());
+        std::env::temp_dir().join(name)
+    }
+
+    #[rstest]
+    #[case::json("json")]
+    #[case::png("png")]
+    #[case::text("txt")]
+    #[case::xml("xml")]
+    fn save_round_trip(#[case] ext: &str) {
+        let expected = nonogram();
+        let path = temp_path("round-trip", ext);
+
+        save_nonogram(&path, &expected).unwrap();
+        let found = load_nonogram(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let found = found.unwrap();
+        assert_eq!(found.rules.rows, expected.rules.rows);
+        assert_eq!(found.rules.cols, expected.rules.cols);
+        assert_eq!(found.colors, expected.colors);
+    }
+
+    #[rstest]
+    #[case::json(JsonWriter::to_string(&nonogram()).unwrap().into_bytes())]
+    #[case::png(ImageWriter::to_png(&nonogram()).unwrap())]
+    #[case::text(write_nonogram(&nonogram()).unwrap().into_bytes())]
+    #[case::xml(write_xml(&nonogram()).unwrap().into_bytes())]
+    fn load_from_reader(#[case] bytes: Vec<u8>) {
+        let expected = nonogram();
+        let found = load_nonogram_from_reader(Cursor::new(bytes)).unwrap();
+
+        assert_eq!(found.rules.rows, expected.rules.rows);
+        assert_eq!(found.rules.cols, expected.rules.cols);
+        assert_eq!(found.colors, expected.colors);
+    }
+
+    #[rstest]
+    #[case::json("\n  {\"rows\": []}", Some(Format::Json))]
+    #[case::xml("<?xml version=\"1.0\"?>", Some(Format::Xml))]
+    #[case::text("# Arrow\n[palette]", Some(Format::Text))]
+    #[case::non("title \"Arrow\"\nwidth 3", Some(Format::Non))]
+    #[case::unknown("hello", None)]
+    #[case::empty("", None)]
+    fn detect(#[case] text: &str, #[case] expected: Option<Format>) {
+        assert_eq!(detect_format(text), expected);
+    }
+
+    #[rstest]
+    #[case::png("png", true)]
+    #[case::non("non", false)]
+    #[case::xml("xml", false)]
+    fn save_not_unique(#[case] ext: &str, #[case] fails: bool) {
+        let picture = Puzzle::new(2, 2, vec![C1, B, B, C1]).unwrap();
+        let nonogram = Nonogram {
+            puzzle: Puzzle::empty(2, 2),
+            rules: Rules::from_puzzle(&picture),
+            colors: vec![(0, 0, 0)],
+            metadata: Metadata::default(),
+        };
+        let path = temp_path("not-unique", ext);
+
+        let saved = save_nonogram(&path, &nonogram);
+        let text = std::fs::read_to_string(&path).unwrap_or_default();
+        std::fs::remove_file(&path).ok();
+
+        // Writers that can leave out the solution do so rather than pick one of the two
+        assert_eq!(saved.is_err(), fails);
+        assert!(!text.contains("goal"));
+    }
+
+    #[test]
+    fn save_unsupported() {
+        let result = save_nonogram(temp_path("unsupported", "bmp"), &nonogram());
+        assert!(matches!(result, Err(Error::UnsupportedExtension(_))));
+    }
+}
@@ -0,0 +1,363 @@
+//! Black and white `.non` format that is used by many nonogram archives and solvers
+//!
+//! ```text
+//! title "Arrow"
+//! by "Someone"
+//! width 3
+//! height 2
+//!
+//! rows
+//! 1,1
+//! 3
+//!
+//! columns
+//! 2
+//! 1
+//! 2
+//!
+//! goal "101111"
+//! ```
+
+use std::{fmt::Write, path::Path};
+
+use nono::{Fill, Metadata, Nonogram, Puzzle, Rule, Rules, Run};
+use thiserror::Error;
+
+use crate::{Error, PuzzleLoader, PuzzleWriter, Result, solve_rules};
+
+pub struct NonLoader;
+
+impl PuzzleLoader for NonLoader {
+    fn load_nonogram(path: &Path) -> Result<Nonogram> {
+        let text = std::fs::read_to_string(path)?;
+        parse_non(&text)
+    }
+}
+
+pub struct NonWriter;
+
+impl PuzzleWriter for NonWriter {
+    fn write_nonogram(path: &Path, nonogram: &Nonogram) -> Result<()> {
+        std::fs::write(path, write_non(nonogram)?)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum NonError {
+    #[error("Invalid {0} '{1}'")]
+    InvalidSize(&'static str, String),
+
+    #[error("Clues for {0} appear before its size")]
+    MissingSize(&'static str),
+
+    #[error("Missing {0}")]
+    Missing(&'static str),
+
+    #[error("Invalid clue '{0}' (should be counts separated by commas like '2,1')")]
+    InvalidClue(String),
+
+    #[error("Expected {expected} lines of clues, found {found}")]
+    TooFewClues { expected: usize, found: usize },
+
+    #[error("Goal has {found} cells (should have {expected})")]
+    GoalLength { expected: usize, found: usize },
+
+    #[error("Invalid goal cell '{0}' (should be '0' or '1')")]
+    InvalidGoal(char),
+
+    #[error("Goal does not match the clues")]
+    GoalMismatch,
+}
+
+/// Parse a nonogram from the `.non` format
+/// Fails with an [`Error::Non`] that holds the line number of the mistake
+///
+/// * `text`: Contents of the file
+pub fn parse_non(text: &str) -> Result<Nonogram> {
+    let lines: Vec<_> = text.lines().map(str::trim).collect();
+    let end = lines.len();
+
+    let mut width = None;
+    let mut height = None;
+    let mut rows = None;
+    let mut cols = None;
+    let mut goal = None;
+    let mut metadata = Metadata::default();
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let number = idx + 1;
+        let (keyword, value) = split_keyword(lines[idx]);
+        idx += 1;
+
+        match keyword {
+            "width" => width = Some(parse_size("width", value, number)?),
+            "height" => height = Some(parse_size("height", value, number)?),
+            "rows" => {
+                let height = height.ok_or(non_error(number, NonError::MissingSize("rows")))?;
+                rows = Some(parse_clues(&lines, &mut idx, height)?);
+            }
+            "columns" => {
+                let width = width.ok_or(non_error(number, NonError::MissingSize("columns")))?;
+                cols = Some(parse_clues(&lines, &mut idx, width)?);
+            }
+            "goal" => goal = Some((number, unquote(value))),
+            "title" => metadata.title = Some(unquote(value).to_string()),
+            "by" | "author" => metadata.author = Some(unquote(value).to_string()),
+            "copyright" => metadata.copyright = Some(unquote(value).to_string()),
+            "" => {}
+            _ => tracing::debug!("Ignored line {number} of .non file: {}", lines[idx - 1]),
+        }
+    }
+
+    let missing = |name| non_error(end, NonError::Missing(name));
+    let width = width.ok_or_else(|| missing("width"))?;
+    let height = height.ok_or_else(|| missing("height"))?;
+    let rows = rows.ok_or_else(|| missing("rows"))?;
+    let cols = cols.ok_or_else(|| missing("columns"))?;
+
+    let row_rules: Vec<_> = rows
+        .into_iter()
+        .map(|runs| Rule::new(runs, width))
+        .collect();
+    let col_rules: Vec<_> = cols
+        .into_iter()
+        .map(|runs| Rule::new(runs, height))
+        .collect();
+    let rules = Rules::new(row_rules, col_rules);
+
+    // The goal is only used to verify the clues, the puzzle itself starts out empty
+    if let Some((number, goal)) = goal {
+        let picture = parse_goal(goal, height, width).map_err(|error| non_error(number, error))?;
+        let expected = Rules::from_puzzle(&picture);
+
+        if expected.rows != rules.rows || expected.cols != rules.cols {
+            return Err(non_error(number, NonError::GoalMismatch));
+        }
+    }
+
+    let nonogram = Nonogram {
+        puzzle: Puzzle::empty(height, width),
+        rules,
+        colors: vec![(0, 0, 0)],
+        metadata,
+    };
+
+    nonogram.check()?;
+    Ok(nonogram)
+}
+
+/// Write a nonogram in the `.non` format, including the goal if the rules have a unique solution
+/// Fails if the nonogram has more than one color
+///
+/// * `nonogram`: Nonogram to write
+pub fn write_non(nonogram: &Nonogram) -> Result<String> {
+    if nonogram.colors.len() > 1 {
+        return Err(Error::Custom(format!(
+            "The .non format only supports a single color, found {}",
+            nonogram.colors.len()
+        )));
+    }
+
+    let mut text = String::new();
+    let metadata = &nonogram.metadata;
+
+    for (keyword, value) in [
+        ("title", &metadata.title),
+        ("by", &metadata.author),
+        ("copyright", &metadata.copyright),
+    ] {
+        if let Some(value) = value {
+            let _ = writeln!(text, "{keyword} \"{}\"", value.replace('"', "'"));
+        }
+    }
+
+    let _ = writeln!(text, "width {}", nonogram.puzzle.cols());
+    let _ = writeln!(text, "height {}", nonogram.puzzle.rows());
+
+    for (keyword, rules) in [
+        ("rows", &nonogram.rules.rows),
+        ("columns", &nonogram.rules.cols),
+    ] {
+        let _ = write!(text, "\n{keyword}\n");
+
+        for rule in rules {
+            let counts: Vec<_> = rule
+                .runs()
+                .iter()
+                .map(|run| run.count.to_string())
+                .collect();
+
+            match counts.is_empty() {
+                true => text.push_str("0\n"),
+                false => {
+                    text.push_str(&counts.join(","));
+                    text.push('\n');
+                }
+            }
+        }
+    }
+
+    match solve_rules(nonogram) {
+        Ok(solution) => {
+            let goal: String = solution
+                .iter_cells()
+                .map(|fill| match fill {
+                    Fill::Color(_) => '1',
+                    _ => '0',
+                })
+                .collect();
+
+            let _ = write!(text, "\ngoal \"{goal}\"\n");
+        }
+        Err(err) => tracing::warn!("Wrote .non file without goal: {err}"),
+    }
+
+    Ok(text)
+}
+
+fn non_error(line: usize, error: NonError) -> Error {
+    Error::Non { line, error }
+}
+
+fn split_keyword(line: &str) -> (&str, &str) {
+    match line.split_once(char::is_whitespace) {
+        Some((keyword, value)) => (keyword, value.trim()),
+        None => (line, ""),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn parse_size(name: &'static str, value: &str, number: usize) -> Result<u16> {
+    match value.parse() {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err(non_error(
+            number,
+            NonError::InvalidSize(name, value.to_string()),
+        )),
+    }
+}
+
+/// Parse the clues of the lines that follow a `rows` or `columns` keyword
+///
+/// * `lines`: All lines of the file
+/// * `idx`: Index of the first line of clues, which is moved past the last one
+/// * `count`: Number of lines of clues
+fn parse_clues(lines: &[&str], idx: &mut usize, count: u16) -> Result<Vec<Vec<Run>>> {
+    let count = count as usize;
+    let mut clues = Vec::with_capacity(count);
+
+    while clues.len() < count {
+        let Some(line) = lines.get(*idx) else {
+            let error = NonError::TooFewClues {
+                expected: count,
+                found: clues.len(),
+            };
+            return Err(non_error(lines.len(), error));
+        };
+
+        let number = *idx + 1;
+        *idx += 1;
+
+        let runs = line
+            .split([',', ' ', '\t'])
+            .filter(|count| !count.is_empty())
+            .map(|count| count.parse::<u16>())
+            .filter(|count| !matches!(count, Ok(0)))
+            .map(|count| count.map(|count| Run::new(Fill::Color(1), count)))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| non_error(number, NonError::InvalidClue(line.to_string())))?;
+
+        clues.push(runs);
+    }
+
+    Ok(clues)
+}
+
+fn parse_goal(goal: &str, rows: u16, cols: u16) -> std::result::Result<Puzzle, NonError> {
+    let fills = goal
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '1' => Ok(Fill::Color(1)),
+            '0' => Ok(Fill::Blank),
+            c => Err(NonError::InvalidGoal(c)),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let expected = rows as usize * cols as usize;
+    if fills.len() != expected {
+        return Err(NonError::GoalLength {
+            expected,
+            found: fills.len(),
+        });
+    }
+
+    Puzzle::new(rows, cols, fills).map_err(|_| NonError::GoalMismatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const B: Fill = Fill::Blank;
+    const C1: Fill = Fill::Color(1);
+
+    const NON: &str = "title \"Arrow\"
+by \"Someone\"
+width 3
+height 2
+
+rows
+1,1
+3
+
+columns
+2
+1
+2
+
+goal \"101111\"
+";
+
+    #[test]
+    fn parse() {
+        let nonogram = parse_non(NON).unwrap();
+
+        assert_eq!(nonogram.metadata.title.as_deref(), Some("Arrow"));
+        assert_eq!(nonogram.metadata.author.as_deref(), Some("Someone"));
+        assert_eq!(
+            nonogram.rules.rows[0].runs(),
+            &vec![Run::new(C1, 1), Run::new(C1, 1)]
+        );
+        assert_eq!(nonogram.rules.cols[1].runs(), &vec![Run::new(C1, 1)]);
+        assert!(nonogram.puzzle.iter_cells().all(|&fill| fill == B));
+    }
+
+    #[test]
+    fn write() {
+        let nonogram = parse_non(NON).unwrap();
+        assert_eq!(write_non(&nonogram).unwrap(), NON);
+    }
+
+    #[rstest]
+    #[case::invalid_size("width x\n", 1)]
+    #[case::missing_size("width 1\nrows\n1\n", 2)]
+    #[case::invalid_clue("width 1\nheight 1\nrows\n1;\n", 4)]
+    #[case::too_few_clues("width 1\nheight 2\nrows\n1\n", 4)]
+    #[case::missing_columns("width 1\nheight 1\nrows\n1\n", 4)]
+    #[case::goal_mismatch("width 1\nheight 1\nrows\n1\ncolumns\n1\ngoal 0\n", 7)]
+    fn parse_error(#[case] text: &str, #[case] expected: usize) {
+        let result = parse_non(text);
+        assert!(matches!(result, Err(Error::Non { line, .. }) if line == expected));
+    }
+}
@@ -0,0 +1,452 @@
+//! Plain text format that is easy to write by hand
+//!
+//! ```text
+//! # Comments take up a whole line
+//! [palette]
+//! 1 #000000
+//! 2 #ff0000
+//!
+//! [rows]
+//! 2 1:2
+//! 0
+//!
+//! [cols]
+//! 1
+//! 1
+//! 1:2
+//!
+//! [grid]
+//! 112
+//! xx.
+//! ```
+//!
+//! Runs are written as their count, followed by the key of their color if it is not the first
+//! Lines without runs are written as `0`, the grid is optional and uses the keys of [`Fill::key`]
+
+use std::{fmt::Write, path::Path};
+
+use nono::{Color, Fill, Metadata, Nonogram, Puzzle, Rule, Rules, Run};
+use thiserror::Error;
+
+use crate::{Error, PuzzleLoader, PuzzleWriter, Result};
+
+pub struct TextLoader;
+
+impl PuzzleLoader for TextLoader {
+    fn load_nonogram(path: &Path) -> Result<Nonogram> {
+        let text = std::fs::read_to_string(path)?;
+        parse_nonogram(&text)
+    }
+}
+
+pub struct TextWriter;
+
+impl PuzzleWriter for TextWriter {
+    fn write_nonogram(path: &Path, nonogram: &Nonogram) -> Result<()> {
+        std::fs::write(path, write_nonogram(nonogram)?)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TextError {
+    #[error("Unknown section '[{0}]'")]
+    UnknownSection(String),
+
+    #[error("Section '[{0}]' appears more than once")]
+    DuplicateSection(&'static str),
+
+    #[error("Missing section '[{0}]'")]
+    MissingSection(&'static str),
+
+    #[error("Content outside of a section: '{0}'")]
+    NoSection(String),
+
+    #[error("Invalid palette entry '{0}' (should be a key and a color like '1 #ff0000')")]
+    InvalidColor(String),
+
+    #[error("Expected palette key '{expected}', found '{found}'")]
+    PaletteOrder { expected: char, found: char },
+
+    #[error("Invalid run '{0}' (should be a count with an optional color key like '3' or '3:2')")]
+    InvalidRun(String),
+
+    #[error("Unknown key '{0}'")]
+    UnknownKey(char),
+
+    #[error("Grid row has {found} cells (should have {expected})")]
+    GridRowLength { expected: usize, found: usize },
+
+    #[error("Grid has {found} rows (should have {expected})")]
+    GridRows { expected: usize, found: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Palette,
+    Rows,
+    Cols,
+    Grid,
+}
+
+impl Section {
+    const ALL: [Section; 4] = [
+        Section::Palette,
+        Section::Rows,
+        Section::Cols,
+        Section::Grid,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Section::Palette => "palette",
+            Section::Rows => "rows",
+            Section::Cols => "cols",
+            Section::Grid => "grid",
+        }
+    }
+}
+
+/// Lines of a section, together with their line numbers
+type SectionLines<'a> = Vec<(usize, &'a str)>;
+
+/// Parse a nonogram from the text format
+/// Fails with an [`Error::Text`] that holds the line number of the mistake
+///
+/// * `text`: Contents of the file
+pub fn parse_nonogram(text: &str) -> Result<Nonogram> {
+    let sections = split_sections(text)?;
+    let end = text.lines().count();
+
+    let section = |section: Section| -> Result<&SectionLines> {
+        sections[section as usize]
+            .as_ref()
+            .ok_or_else(|| text_error(end, TextError::MissingSection(section.name())))
+    };
+
+    let colors = parse_palette(section(Section::Palette)?)?;
+    let row_lines = section(Section::Rows)?;
+    let col_lines = section(Section::Cols)?;
+
+    let rows = row_lines.len() as u16;
+    let cols = col_lines.len() as u16;
+
+    let row_rules = parse_rules(row_lines, cols)?;
+    let col_rules = parse_rules(col_lines, rows)?;
+
+    let puzzle = match &sections[Section::Grid as usize] {
+        Some(lines) => parse_grid(lines, rows, cols, end)?,
+        None => Puzzle::empty(rows, cols),
+    };
+
+    let nonogram = Nonogram {
+        puzzle,
+        rules: Rules::new(row_rules, col_rules),
+        colors,
+        metadata: Metadata::default(),
+    };
+
+    nonogram.check()?;
+    Ok(nonogram)
+}
+
+/// Write a nonogram in the text format, including the grid if any cell is filled in
+///
+/// * `nonogram`: Nonogram to write
+pub fn write_nonogram(nonogram: &Nonogram) -> Result<String> {
+    let color_count = nonogram.colors.len() as u16;
+    let key = |fill: Fill| -> Result<char> {
+        fill.key(Some(color_count))
+            .ok_or_else(|| Error::Custom(format!("{fill:?} has no key in the text format")))
+    };
+
+    let mut text = String::from("[palette]\n");
+
+    for (idx, (r, g, b)) in nonogram.colors.iter().enumerate() {
+        let key = key(Fill::Color(idx as u16 + 1))?;
+        let _ = writeln!(text, "{key} #{r:02x}{g:02x}{b:02x}");
+    }
+
+    for (name, rules) in [
+        ("rows", &nonogram.rules.rows),
+        ("cols", &nonogram.rules.cols),
+    ] {
+        let _ = write!(text, "\n[{name}]\n");
+
+        for rule in rules {
+            let runs: Vec<_> = rule
+                .runs()
+                .iter()
+                .map(|run| match run.fill {
+                    Fill::Color(1) => Ok(run.count.to_string()),
+                    fill => Ok(format!("{}:{}", run.count, key(fill)?)),
+                })
+                .collect::<Result<_>>()?;
+
+            match runs.is_empty() {
+                true => text.push_str("0\n"),
+                false => {
+                    text.push_str(&runs.join(" "));
+                    text.push('\n');
+                }
+            }
+        }
+    }
+
+    let puzzle = &nonogram.puzzle;
+    if puzzle.iter_cells().any(|&fill| fill != Fill::Blank) {
+        text.push_str("\n[grid]\n");
+
+        let fills: Vec<_> = puzzle.iter_cells().copied().collect();
+        for row in fills.chunks(puzzle.cols().max(1) as usize) {
+            for &fill in row {
+                text.push(key(fill)?);
+            }
+            text.push('\n');
+        }
+    }
+
+    Ok(text)
+}
+
+fn text_error(line: usize, error: TextError) -> Error {
+    Error::Text { line, error }
+}
+
+/// Group the non-empty lines by the section they are in, in the order of [`Section::ALL`]
+fn split_sections(text: &str) -> Result<[Option<SectionLines<'_>>; 4]> {
+    let mut sections: [Option<SectionLines>; 4] = Default::default();
+    let mut current: Option<usize> = None;
+
+    for (idx, line) in text.lines().enumerate() {
+        let number = idx + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            let name = name.trim().to_lowercase();
+            let Some(idx) = Section::ALL.iter().position(|s| s.name() == name) else {
+                return Err(text_error(number, TextError::UnknownSection(name)));
+            };
+
+            if sections[idx].is_some() {
+                let error = TextError::DuplicateSection(Section::ALL[idx].name());
+                return Err(text_error(number, error));
+            }
+
+            sections[idx] = Some(Vec::new());
+            current = Some(idx);
+            continue;
+        }
+
+        match current.and_then(|idx| sections[idx].as_mut()) {
+            Some(lines) => lines.push((number, line)),
+            None => return Err(text_error(number, TextError::NoSection(line.to_string()))),
+        }
+    }
+
+    Ok(sections)
+}
+
+fn parse_palette(lines: &SectionLines) -> Result<Vec<Color>> {
+    let mut colors = Vec::new();
+
+    for &(number, line) in lines {
+        let invalid = || text_error(number, TextError::InvalidColor(line.to_string()));
+
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(hex), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+
+        let mut chars = key.chars();
+        let (Some(key), None) = (chars.next(), chars.next()) else {
+            return Err(invalid());
+        };
+
+        // Keys have to follow the order of the color ids
+        let id = colors.len() as u16 + 1;
+        let expected = Fill::Color(id).key(None).ok_or_else(invalid)?;
+        if key != expected {
+            let error = TextError::PaletteOrder {
+                expected,
+                found: key,
+            };
+            return Err(text_error(number, error));
+        }
+
+        colors.push(parse_color(hex).ok_or_else(invalid)?);
+    }
+
+    Ok(colors)
+}
+
+fn parse_color(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn parse_rules(lines: &SectionLines, line_len: u16) -> Result<Vec<Rule>> {
+    lines
+        .iter()
+        .map(|&(number, line)| {
+            let runs = line
+                .split_whitespace()
+                .map(|token| parse_run(token).map_err(|error| text_error(number, error)))
+                .filter(|run| !matches!(run, Ok(run) if run.count == 0))
+                .collect::<Result<_>>()?;
+
+            Ok(Rule::new(runs, line_len))
+        })
+        .collect()
+}
+
+fn parse_run(token: &str) -> std::result::Result<Run, TextError> {
+    let invalid = || TextError::InvalidRun(token.to_string());
+
+    let (count, fill) = match token.split_once(':') {
+        Some((count, key)) => {
+            let mut chars = key.chars();
+            let (Some(key), None) = (chars.next(), chars.next()) else {
+                return Err(invalid());
+            };
+
+            match parse_key(key)? {
+                fill @ Fill::Color(_) => (count, fill),
+                _ => return Err(invalid()),
+            }
+        }
+        None => (token, Fill::Color(1)),
+    };
+
+    let count = count.parse().map_err(|_| invalid())?;
+    Ok(Run::new(fill, count))
+}
+
+/// Find the fill that has the given key
+fn parse_key(key: char) -> std::result::Result<Fill, TextError> {
+    let fills = [Fill::Blank, Fill::Cross]
+        .into_iter()
+        .chain((1..=u8::MAX as u16).map(Fill::Color));
+
+    fills
+        .take_while(|fill| !matches!(fill, Fill::Color(_)) || fill.key(None).is_some())
+        .find(|fill| fill.key(None) == Some(key))
+        .ok_or(TextError::UnknownKey(key))
+}
+
+fn parse_grid(lines: &SectionLines, rows: u16, cols: u16, end: usize) -> Result<Puzzle> {
+    if lines.len() != rows as usize {
+        let error = TextError::GridRows {
+            expected: rows as usize,
+            found: lines.len(),
+        };
+        let number = lines.get(rows as usize).map_or(end, |&(number, _)| number);
+        return Err(text_error(number, error));
+    }
+
+    let mut fills = Vec::with_capacity(rows as usize * cols as usize);
+
+    for &(number, line) in lines {
+        let row: Vec<_> = line
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|key| parse_key(key).map_err(|error| text_error(number, error)))
+            .collect::<Result<_>>()?;
+
+        if row.len() != cols as usize {
+            let error = TextError::GridRowLength {
+                expected: cols as usize,
+                found: row.len(),
+            };
+            return Err(text_error(number, error));
+        }
+
+        fills.extend(row);
+    }
+
+    Ok(Puzzle::new(rows, cols, fills)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const B: Fill = Fill::Blank;
+    const X: Fill = Fill::Cross;
+    const C1: Fill = Fill::Color(1);
+    const C2: Fill = Fill::Color(2);
+
+    const TEXT: &str = "# Small puzzle
+[palette]
+1 #000000
+2 #ff0000
+
+[rows]
+2 1:2
+0
+
+[cols]
+1
+1
+1:2
+
+[grid]
+112
+xx.
+";
+
+    #[test]
+    fn parse_text() {
+        let nonogram = parse_nonogram(TEXT).unwrap();
+
+        assert_eq!(nonogram.colors, vec![(0, 0, 0), (255, 0, 0)]);
+        assert_eq!(
+            nonogram.rules.rows[0].runs(),
+            &vec![Run::new(C1, 2), Run::new(C2, 1)]
+        );
+        assert!(nonogram.rules.rows[1].runs().is_empty());
+        assert_eq!(nonogram.rules.cols[2].runs(), &vec![Run::new(C2, 1)]);
+        assert!(nonogram.puzzle.iter_cells().eq(&[C1, C1, C2, X, X, B]));
+    }
+
+    #[test]
+    fn write_text() {
+        let nonogram = parse_nonogram(TEXT).unwrap();
+        let text = write_nonogram(&nonogram).unwrap();
+        let expected: String = TEXT
+            .lines()
+            .skip(1)
+            .map(|line| format!("{line}\n"))
+            .collect();
+
+        assert_eq!(text, expected);
+    }
+
+    #[rstest]
+    #[case::unknown_section("[palette]\n1 #000000\n[clues]\n", 3)]
+    #[case::no_section("1 #000000\n", 1)]
+    #[case::invalid_color("[palette]\n1 #00000g\n", 2)]
+    #[case::palette_order("[palette]\n2 #000000\n", 2)]
+    #[case::invalid_run("[palette]\n1 #000000\n[rows]\n1\n1:\n[cols]\n1\n1\n", 5)]
+    #[case::unknown_key("[palette]\n1 #000000\n[rows]\n1\n[cols]\n1\n[grid]\n?\n", 8)]
+    #[case::grid_row("[palette]\n1 #000000\n[rows]\n1\n[cols]\n1\n[grid]\n1.\n", 8)]
+    #[case::missing_cols("[palette]\n1 #000000\n[rows]\n1\n", 4)]
+    fn parse_error(#[case] text: &str, #[case] expected: usize) {
+        let result = parse_nonogram(text);
+        assert!(matches!(result, Err(Error::Text { line, .. }) if line == expected));
+    }
+}
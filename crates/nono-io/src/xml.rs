@@ -0,0 +1,609 @@
+//! XML format of the web paint-by-number community
+//!
+//! ```xml
+//! <puzzleset>
+//!   <puzzle type="grid" defaultcolor="black">
+//!     <title>Arrow</title>
+//!     <color name="white" char=".">fff</color>
+//!     <color name="black" char="X">000</color>
+//!     <color name="red" char="r">f00</color>
+//!     <clues type="columns">
+//!       <line><count>1</count><count color="red">1</count></line>
+//!     </clues>
+//!     <clues type="rows">
+//!       <line><count>1</count></line>
+//!       <line><count color="red">1</count></line>
+//!     </clues>
+//!     <solution type="goal">
+//!       <image>|X|r|</image>
+//!     </solution>
+//!   </puzzle>
+//! </puzzleset>
+//! ```
+//!
+//! Only the first puzzle of a puzzle set is read. Goal solutions are checked against the clues,
+//! or used to derive them if the puzzle has none, while saved solutions are loaded as the puzzle
+
+use std::{collections::HashMap, fmt::Write, path::Path};
+
+use nono::{Color, Fill, Metadata, Nonogram, Puzzle, Rule, Rules, Run};
+use roxmltree::{Document, Node, ParsingOptions};
+use thiserror::Error;
+
+use crate::{Error, PuzzleLoader, PuzzleWriter, Result, solve_rules};
+
+pub struct XmlLoader;
+
+impl PuzzleLoader for XmlLoader {
+    fn load_nonogram(path: &Path) -> Result<Nonogram> {
+        let text = std::fs::read_to_string(path)?;
+        parse_xml(&text)
+    }
+}
+
+pub struct XmlWriter;
+
+impl PuzzleWriter for XmlWriter {
+    fn write_nonogram(path: &Path, nonogram: &Nonogram) -> Result<()> {
+        std::fs::write(path, write_xml(nonogram)?)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum XmlError {
+    #[error("{0}")]
+    Parse(#[from] roxmltree::Error),
+
+    #[error("Missing <{0}> element")]
+    Missing(&'static str),
+
+    #[error("Invalid color value '{0}'")]
+    InvalidColor(String),
+
+    #[error("Unknown color '{0}'")]
+    UnknownColor(String),
+
+    #[error("Invalid count '{0}'")]
+    InvalidCount(String),
+
+    #[error("Unknown cell '{0}' in solution image")]
+    UnknownCell(char),
+
+    #[error("Row {row} of the solution image has {found} cells (should have {expected})")]
+    RowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error(
+        "Solution image has {rows} rows of {cols} cells (should have {expected_rows} of {expected_cols})"
+    )]
+    ImageSize {
+        rows: usize,
+        cols: usize,
+        expected_rows: usize,
+        expected_cols: usize,
+    },
+
+    #[error("Goal does not match the clues")]
+    GoalMismatch,
+}
+
+/// Colors of a puzzle by their name and character, where the background is blank
+struct Palette {
+    colors: Vec<Color>,
+    names: HashMap<String, Fill>,
+    chars: HashMap<char, Fill>,
+    default: String,
+}
+
+impl Palette {
+    fn fill_by_name(&self, name: &str) -> std::result::Result<Fill, XmlError> {
+        self.names
+            .get(name)
+            .copied()
+            .ok_or_else(|| XmlError::UnknownColor(name.to_string()))
+    }
+}
+
+/// Parse the first puzzle of an XML puzzle set
+///
+/// * `text`: Contents of the file
+pub fn parse_xml(text: &str) -> Result<Nonogram> {
+    // Files from the community start with a doctype that refers to their DTD
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+
+    let document = Document::parse_with_options(text, options).map_err(XmlError::from)?;
+    let puzzle = document
+        .descendants()
+        .find(|node| node.has_tag_name("puzzle"))
+        .ok_or(XmlError::Missing("puzzle"))?;
+
+    let palette = parse_palette(puzzle)?;
+    let metadata = Metadata {
+        title: child_text(puzzle, "title"),
+        author: child_text(puzzle, "author"),
+        copyright: child_text(puzzle, "copyright"),
+    };
+
+    let clues = |kind: &str| -> std::result::Result<Option<Vec<Vec<Run>>>, XmlError> {
+        puzzle
+            .children()
+            .find(|node| node.has_tag_name("clues") && node.attribute("type") == Some(kind))
+            .map(|clues| parse_clues(clues, &palette))
+            .transpose()
+    };
+
+    let rows = clues("rows")?;
+    let cols = clues("columns")?;
+
+    let image = |kind: &str| {
+        puzzle
+            .children()
+            .filter(|node| node.has_tag_name("solution"))
+            .find(|node| node.attribute("type").unwrap_or("goal") == kind)
+            .and_then(|node| node.children().find(|node| node.has_tag_name("image")))
+            .and_then(|node| node.text())
+    };
+
+    let goal = match image("goal").or_else(|| image("solution")) {
+        Some(image) => Some(parse_image(image, &palette, Fill::Blank)?),
+        None => None,
+    };
+
+    // Derive the clues from the goal if the puzzle only has a solution
+    let rules = match (rows, cols, &goal) {
+        (Some(rows), Some(cols), goal) => {
+            let (height, width) = (rows.len() as u16, cols.len() as u16);
+            let rules = Rules::new(
+                rows.into_iter()
+                    .map(|runs| Rule::new(runs, width))
+                    .collect(),
+                cols.into_iter()
+                    .map(|runs| Rule::new(runs, height))
+                    .collect(),
+            );
+
+            if let Some(goal) = goal {
+                let expected = Rules::from_puzzle(goal);
+                if expected.rows != rules.rows || expected.cols != rules.cols {
+                    return Err(XmlError::GoalMismatch.into());
+                }
+            }
+
+            rules
+        }
+        (_, _, Some(goal)) => Rules::from_puzzle(goal),
+        (None, _, None) => return Err(XmlError::Missing("clues type=\"rows\"").into()),
+        (_, None, None) => return Err(XmlError::Missing("clues type=\"columns\"").into()),
+    };
+
+    let (rows, cols) = (rules.rows.len() as u16, rules.cols.len() as u16);
+
+    // Saved solutions hold the progress, where background cells are known to be empty
+    let puzzle = match image("saved") {
+        Some(image) => {
+            let saved = parse_image(image, &palette, Fill::Cross)?;
+            check_image_size(&saved, rows, cols)?;
+            saved
+        }
+        None => Puzzle::empty(rows, cols),
+    };
+
+    if let Some(goal) = &goal {
+        check_image_size(goal, rows, cols)?;
+    }
+
+    let nonogram = Nonogram {
+        puzzle,
+        rules,
+        colors: palette.colors,
+        metadata,
+    };
+
+    nonogram.check()?;
+    Ok(nonogram)
+}
+
+/// Write a nonogram as a puzzle set with a single puzzle
+/// The goal is included if the rules have a unique solution, the progress if any cell is filled in
+///
+/// * `nonogram`: Nonogram to write
+pub fn write_xml(nonogram: &Nonogram) -> Result<String> {
+    let name = |id: u16| match nonogram.colors.len() {
+        1 => "black".to_string(),
+        _ => format!("color{id}"),
+    };
+
+    let color_count = nonogram.colors.len() as u16;
+    let key = |fill: Fill| -> Result<char> {
+        match fill {
+            Fill::Color(_) => fill
+                .key(Some(color_count))
+                .ok_or_else(|| Error::Custom(format!("{fill:?} has no character in XML"))),
+            Fill::Blank => Ok('?'),
+            Fill::Cross => Ok('.'),
+        }
+    };
+
+    let mut xml = String::from("<?xml version=\"1.0\"?>\n<puzzleset>\n");
+    let _ = writeln!(
+        xml,
+        "<puzzle type=\"grid\" defaultcolor=\"{}\" backgroundcolor=\"white\">",
+        name(1)
+    );
+
+    let metadata = &nonogram.metadata;
+    for (tag, value) in [
+        ("title", &metadata.title),
+        ("author", &metadata.author),
+        ("copyright", &metadata.copyright),
+    ] {
+        if let Some(value) = value {
+            let _ = writeln!(xml, "<{tag}>{}</{tag}>", escape(value));
+        }
+    }
+
+    xml.push_str("<color name=\"white\" char=\".\">ffffff</color>\n");
+
+    for (idx, (r, g, b)) in nonogram.colors.iter().enumerate() {
+        let id = idx as u16 + 1;
+        let key = key(Fill::Color(id))?;
+        let _ = writeln!(
+            xml,
+            "<color name=\"{}\" char=\"{key}\">{r:02x}{g:02x}{b:02x}</color>",
+            name(id)
+        );
+    }
+
+    for (kind, rules) in [
+        ("columns", &nonogram.rules.cols),
+        ("rows", &nonogram.rules.rows),
+    ] {
+        let _ = writeln!(xml, "<clues type=\"{kind}\">");
+
+        for rule in rules {
+            xml.push_str("<line>");
+
+            for run in rule.runs() {
+                match run.fill {
+                    Fill::Color(1) => {
+                        let _ = write!(xml, "<count>{}</count>", run.count);
+                    }
+                    Fill::Color(id) => {
+                        let _ = write!(xml, "<count color=\"{}\">{}</count>", name(id), run.count);
+                    }
+                    _ => {}
+                }
+            }
+
+            xml.push_str("</line>\n");
+        }
+
+        xml.push_str("</clues>\n");
+    }
+
+    match solve_rules(nonogram) {
+        Ok(solution) => write_image(&mut xml, "goal", &solution, key)?,
+        Err(err) => tracing::warn!("Wrote XML file without goal: {err}"),
+    }
+
+    let puzzle = &nonogram.puzzle;
+    if puzzle.iter_cells().any(|&fill| fill != Fill::Blank) {
+        write_image(&mut xml, "saved", puzzle, key)?;
+    }
+
+    xml.push_str("</puzzle>\n</puzzleset>\n");
+    Ok(xml)
+}
+
+fn write_image(
+    xml: &mut String,
+    kind: &str,
+    puzzle: &Puzzle,
+    key: impl Fn(Fill) -> Result<char>,
+) -> Result<()> {
+    let _ = write!(xml, "<solution type=\"{kind}\">\n<image>\n");
+
+    let fills: Vec<_> = puzzle.iter_cells().copied().collect();
+    for row in fills.chunks(puzzle.cols().max(1) as usize) {
+        xml.push('|');
+        for &fill in row {
+            xml.push(match fill {
+                // Goals only have colored and empty cells
+                Fill::Blank if kind == "goal" => '.',
+                fill => key(fill)?,
+            });
+        }
+        xml.push_str("|\n");
+    }
+
+    xml.push_str("</image>\n</solution>\n");
+    Ok(())
+}
+
+fn child_text(node: Node, tag: &str) -> Option<String> {
+    node.children()
+        .find(|node| node.has_tag_name(tag))
+        .and_then(|node| node.text())
+        .map(|text| text.trim().to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn parse_palette(puzzle: Node) -> std::result::Result<Palette, XmlError> {
+    let background = puzzle.attribute("backgroundcolor").unwrap_or("white");
+    let default = puzzle.attribute("defaultcolor").unwrap_or("black");
+
+    let mut palette = Palette {
+        colors: Vec::new(),
+        names: HashMap::new(),
+        chars: HashMap::new(),
+        default: default.to_string(),
+    };
+
+    for node in puzzle.children().filter(|node| node.has_tag_name("color")) {
+        let name = node.attribute("name").unwrap_or_default();
+        let value = node.text().unwrap_or_default().trim();
+
+        let fill = match name == background {
+            true => Fill::Blank,
+            false => {
+                palette.colors.push(parse_color(value)?);
+                Fill::Color(palette.colors.len() as u16)
+            }
+        };
+
+        palette.names.insert(name.to_string(), fill);
+        if let Some(c) = node
+            .attribute("char")
+            .and_then(|chars| chars.chars().next())
+        {
+            palette.chars.insert(c, fill);
+        }
+    }
+
+    // Puzzles without a palette are black and white
+    if palette.colors.is_empty() {
+        palette.colors.push((0, 0, 0));
+        palette.names.insert(default.to_string(), Fill::Color(1));
+        palette.chars.insert('X', Fill::Color(1));
+        palette.chars.insert('.', Fill::Blank);
+    }
+
+    Ok(palette)
+}
+
+/// Parse a color of 3 or 6 hex digits
+fn parse_color(value: &str) -> std::result::Result<Color, XmlError> {
+    let invalid = || XmlError::InvalidColor(value.to_string());
+    let hex = value.strip_prefix('#').unwrap_or(value);
+
+    if !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+
+    match hex.len() {
+        3 => {
+            let short = |idx: usize| channel(&hex[idx..idx + 1]).map(|value| value * 17);
+            Ok((short(0)?, short(1)?, short(2)?))
+        }
+        6 => Ok((
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_clues(clues: Node, palette: &Palette) -> std::result::Result<Vec<Vec<Run>>, XmlError> {
+    clues
+        .children()
+        .filter(|node| node.has_tag_name("line"))
+        .map(|line| {
+            line.children()
+                .filter(|node| node.has_tag_name("count"))
+                .map(|count| {
+                    let name = count.attribute("color").unwrap_or(&palette.default);
+                    let fill = palette.fill_by_name(name)?;
+
+                    let text = count.text().unwrap_or_default().trim();
+                    let count = text
+                        .parse()
+                        .map_err(|_| XmlError::InvalidCount(text.to_string()))?;
+
+                    Ok(Run::new(fill, count))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Parse a solution image, where each row is written in between `|` characters
+///
+/// * `image`: Text of the image
+/// * `palette`: Palette with the characters of the colors
+/// * `background`: Fill of cells with the background color
+fn parse_image(image: &str, palette: &Palette, background: Fill) -> Result<Puzzle> {
+    let rows: Vec<Vec<Fill>> = image
+        .lines()
+        .map(|line| line.trim().trim_matches('|'))
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.chars()
+                .map(|c| match (c, palette.chars.get(&c)) {
+                    (_, Some(Fill::Blank)) => Ok(background),
+                    (_, Some(&fill)) => Ok(fill),
+                    ('?', None) => Ok(Fill::Blank),
+                    (c, None) => Err(XmlError::UnknownCell(c)),
+                })
+                .collect()
+        })
+        .collect::<std::result::Result<_, _>>()?;
+
+    let cols = rows.first().map_or(0, Vec::len);
+    if let Some((row, fills)) = rows.iter().enumerate().find(|(_, row)| row.len() != cols) {
+        return Err(XmlError::RowLength {
+            row,
+            expected: cols,
+            found: fills.len(),
+        }
+        .into());
+    }
+
+    let (height, width) = (rows.len() as u16, cols as u16);
+    let fills = rows.into_iter().flatten().collect();
+    let puzzle = Puzzle::new(height, width, fills)?;
+
+    Ok(puzzle)
+}
+
+fn check_image_size(image: &Puzzle, rows: u16, cols: u16) -> std::result::Result<(), XmlError> {
+    if image.rows() != rows || image.cols() != cols {
+        return Err(XmlError::ImageSize {
+            rows: image.rows() as usize,
+            cols: image.cols() as usize,
+            expected_rows: rows as usize,
+            expected_cols: cols as usize,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const B: Fill = Fill::Blank;
+    const X: Fill = Fill::Cross;
+    const C1: Fill = Fill::Color(1);
+    const C2: Fill = Fill::Color(2);
+
+    const XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE pbn SYSTEM "https://webpbn.com/pbn-0.3.dtd">
+<puzzleset>
+<puzzle type="grid" defaultcolor="black">
+<title>Arrow &amp; Bow</title>
+<author>Someone</author>
+<color name="white" char=".">fff</color>
+<color name="black" char="X">000</color>
+<color name="red" char="r">ff0000</color>
+<clues type="columns">
+<line><count>1</count><count color="red">1</count></line>
+<line><count color="red">1</count></line>
+</clues>
+<clues type="rows">
+<line><count>1</count></line>
+<line><count color="red">2</count></line>
+</clues>
+<solution type="goal">
+<image>
+|X.|
+|rr|
+</image>
+</solution>
+<solution type="saved">
+<image>
+|X.|
+|??|
+</image>
+</solution>
+</puzzle>
+</puzzleset>
+"#;
+
+    #[test]
+    fn parse() {
+        let nonogram = parse_xml(XML).unwrap();
+
+        assert_eq!(nonogram.colors, vec![(0, 0, 0), (255, 0, 0)]);
+        assert_eq!(nonogram.metadata.title.as_deref(), Some("Arrow & Bow"));
+        assert_eq!(
+            nonogram.rules.cols[0].runs(),
+            &vec![Run::new(C1, 1), Run::new(C2, 1)]
+        );
+        assert_eq!(nonogram.rules.rows[1].runs(), &vec![Run::new(C2, 2)]);
+        assert!(nonogram.puzzle.iter_cells().eq(&[C1, X, B, B]));
+    }
+
+    #[test]
+    fn round_trip() {
+        let expected = parse_xml(XML).unwrap();
+        let found = parse_xml(&write_xml(&expected).unwrap()).unwrap();
+
+        assert_eq!(found.rules.rows, expected.rules.rows);
+        assert_eq!(found.rules.cols, expected.rules.cols);
+        assert_eq!(found.colors, expected.colors);
+        assert_eq!(found.metadata, expected.metadata);
+        assert!(found.puzzle.iter_cells().eq(expected.puzzle.iter_cells()));
+    }
+
+    #[test]
+    fn goal_only() {
+        let xml = "<puzzleset><puzzle><solution><image>|X.|\n|XX|</image></solution></puzzle></puzzleset>";
+        let nonogram = parse_xml(xml).unwrap();
+
+        assert_eq!(nonogram.colors, vec![(0, 0, 0)]);
+        assert_eq!(nonogram.rules.rows[1].runs(), &vec![Run::new(C1, 2)]);
+    }
+
+    #[rstest]
+    #[case::syntax("<puzzleset><puzzle></puzzleset>")]
+    #[case::no_puzzle("<puzzleset></puzzleset>")]
+    #[case::no_clues("<puzzleset><puzzle></puzzle></puzzleset>")]
+    #[case::unknown_color(
+        r#"<puzzle><clues type="rows"><line><count color="blue">1</count></line></clues></puzzle>"#
+    )]
+    #[case::mismatch(r#"<puzzle><clues type="rows"><line><count>1</count></line></clues><clues type="columns"><line><count>1</count></line></clues><solution><image>|.|</image></solution></puzzle>"#)]
+    fn parse_error(#[case] xml: &str) {
+        assert!(parse_xml(xml).is_err());
+    }
+
+    #[test]
+    fn ragged_image() {
+        let xml = "<puzzle><solution><image>|XX|\n|X|\n|..|</image></solution></puzzle>";
+        let err = parse_xml(xml).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::Xml(XmlError::RowLength {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn saved_image_size() {
+        let saved = r#"<solution type="saved"><image>|X.|</image></solution>"#;
+        let xml = format!("<puzzle><solution><image>|X|\n|.|</image></solution>{saved}</puzzle>");
+        let err = parse_xml(&xml).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::Xml(XmlError::ImageSize {
+                rows: 1,
+                cols: 2,
+                expected_rows: 2,
+                expected_cols: 1
+            })
+        ));
+    }
+}
@@ -29,7 +29,11 @@ impl History {
         self.redos.clear();
 
         let result = action.execute(state);
-        self.undos.push(action);
+
+        // Actions that did not change anything are not worth undoing
+        if !matches!(result, Ok(ActionOutcome::Ignored)) {
+            self.undos.push(action);
+        }
 
         result
     }
@@ -3,13 +3,14 @@ use std::path::PathBuf;
 use crate::PuzzleStyle;
 use clap::Parser;
 use nono::Nonogram;
-use nono_io::load_nonogram;
+use nono_io::{load_nonogram, load_nonogram_from_reader};
 
 use crate::Result;
 
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = None)]
 pub struct Args {
+    /// Puzzle file to open, or `-` to read it from stdin
     pub file: PathBuf,
 
     #[arg(short = 'x', long, default_value_t = 'X')]
@@ -31,7 +32,11 @@ impl Args {
     }
 
     pub fn parse_puzzle(&self) -> Result<Nonogram> {
-        let nonogram = load_nonogram(&self.file)?;
+        let nonogram = match self.file.as_os_str() == "-" {
+            true => load_nonogram_from_reader(std::io::stdin().lock())?,
+            false => load_nonogram(&self.file)?,
+        };
+
         Ok(nonogram)
     }
 }
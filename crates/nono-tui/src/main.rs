@@ -17,9 +17,13 @@ pub use events::*;
 pub use log::*;
 pub use widgets::*;
 
-use std::path::Path;
+use std::{path::Path, time::Duration};
 
 use clap::Parser;
+use nono::{SolveOptions, Solver};
+
+/// Time the difficulty rating may take before the app starts, as hard puzzles may take forever
+const RATING_TIME_LIMIT: Duration = Duration::from_secs(1);
 
 fn main() -> Result<()> {
     let args = Args::parse();
@@ -38,6 +42,16 @@ fn main() -> Result<()> {
 
     let nonogram = args.parse_puzzle()?;
 
+    // Rate the puzzle before it is split up for the app, leaving it unrated if that takes too long
+    let options = SolveOptions::new().time_limit(RATING_TIME_LIMIT);
+    let difficulty = match Solver::new().rate_difficulty_with(&nonogram, &options) {
+        Ok(rating) => Some(rating.difficulty),
+        Err(err) => {
+            tracing::warn!("Couldn't rate the puzzle difficulty: {err}");
+            None
+        }
+    };
+
     let puzzle = nonogram.puzzle;
     let rules = nonogram.rules;
 
@@ -49,6 +63,7 @@ fn main() -> Result<()> {
 
     let mut term = ratatui::init();
     let mut app = App::new(puzzle, rules, style, config);
+    app.state.footer.difficulty = difficulty;
 
     if let Err(err) = app.run(&mut term) {
         tracing::error!("{err:#?}");
@@ -225,10 +225,17 @@ impl FooterWidget {
         selection_span.render(area, buf);
 
         // Right
-        // Show the dimensions of the puzzle
+        // Show the difficulty and dimensions of the puzzle
+        let difficulty = state
+            .footer
+            .difficulty
+            .map_or("Unrated ".to_string(), |difficulty| {
+                format!("{difficulty} ")
+            });
+
         Span::styled(
             format!(
-                "{},{}",
+                "{difficulty}{},{}",
                 state.puzzle.puzzle.rows(),
                 state.puzzle.puzzle.cols()
             ),
@@ -1,4 +1,4 @@
-use nono::{Axis, Fill};
+use nono::{Axis, Difficulty, Fill};
 use ratatui::layout::Rect;
 
 use crate::Region;
@@ -8,6 +8,8 @@ pub struct FooterState {
     pub axis_region: Region<Axis>,
     pub fill_regions: Vec<Region<Fill>>,
 
+    pub difficulty: Option<Difficulty>,
+
     pub area: Rect,
 }
 
@@ -28,22 +28,24 @@ impl CellChange {
 
 impl UndoAction for FillAction {
     fn execute(&mut self, state: &mut AppState) -> ActionResult {
-        for change in &self.changes {
-            let puzzle = &mut state.puzzle.puzzle;
+        let puzzle = &mut state.puzzle.puzzle;
 
-            // Then update the cell state in the solver
-            state.solver.update_cell(puzzle, change.pos, change.after);
-        }
+        // Only keep the changes the solver made, as it refuses to change locked cells
+        self.changes
+            .retain(|change| state.solver.update_cell(puzzle, change.pos, change.after));
 
-        Ok(ActionOutcome::Consumed)
+        match self.changes.is_empty() {
+            true => Ok(ActionOutcome::Ignored),
+            false => Ok(ActionOutcome::Consumed),
+        }
     }
 
     fn undo(&mut self, state: &mut AppState) -> ActionResult {
         for change in &self.changes {
             let puzzle = &mut state.puzzle.puzzle;
 
-            // Then update the cell state in the solver
-            state.solver.update_cell(puzzle, change.pos, change.before);
+            // Only changes to cells that are not locked were kept, so restoring them cannot fail
+            let _ = state.solver.update_cell(puzzle, change.pos, change.before);
         }
 
         Ok(ActionOutcome::Consumed)
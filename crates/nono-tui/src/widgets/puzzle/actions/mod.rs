@@ -309,8 +309,8 @@ fn handle_fills(fill: Fill, range: Option<MotionRange>, state: &AppState) -> Act
         let pos = app_to_puzzle(pos);
         let before = state.puzzle.puzzle[pos];
 
-        // Only record actual changes
-        if before == fill {
+        // Only record actual changes to cells that are not given
+        if before == fill || state.puzzle.puzzle.is_locked(pos) {
             continue;
         }
 
@@ -8,7 +8,7 @@ pub use state::*;
 pub use style::*;
 pub use viewport::*;
 
-use nono::Fill;
+use nono::{Fill, Line};
 use ratatui::{
     buffer::Buffer,
     layout::{Position as AppPosition, Rect},
@@ -164,6 +164,25 @@ impl PuzzleWidget {
             }
         };
 
+        // Givens of the puzzle cannot be changed
+        if state.puzzle.puzzle.is_locked(app_to_puzzle(pos)) {
+            style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        }
+
+        // Cells that violate the rule of their row or column
+        let is_violated = [Line::Row(pos.y), Line::Col(pos.x)]
+            .into_iter()
+            .zip([pos.x, pos.y])
+            .any(|(line, offset)| {
+                state.solver[line]
+                    .violation()
+                    .is_some_and(|violation| violation.contains(offset))
+            });
+
+        if is_violated {
+            style = style.bg(Color::Red);
+        }
+
         // Active line
         if matches!(state.focus, Focus::Puzzle) {
             if pos.x == state.puzzle.cursor.x || pos.y == state.puzzle.cursor.y {
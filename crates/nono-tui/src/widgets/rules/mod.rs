@@ -35,6 +35,12 @@ pub fn run_style(info: &RuleInfo, fill: Fill, idx: u16, state: &AppState) -> Sty
 
     let base = Style::default().fg(color);
 
+    // Only shade the run that is violated if it is known, otherwise the whole rule
+    let is_invalid = match validation.violation() {
+        Some(violation) => violation.run.is_none_or(|run| run == idx),
+        None => !validation.is_valid(),
+    };
+
     let mut style = match validation {
         // Cross out solved lines
         LineValidation::Solved => base
@@ -42,7 +48,7 @@ pub fn run_style(info: &RuleInfo, fill: Fill, idx: u16, state: &AppState) -> Sty
             .add_modifier(Modifier::DIM | Modifier::CROSSED_OUT),
 
         // Shade invalid rules in red
-        v if !v.is_valid() => base
+        _ if is_invalid => base
             .fg(Color::Red)
             .add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
 
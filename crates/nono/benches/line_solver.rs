@@ -0,0 +1,45 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use nono::{Fill, LineAutomaton, LineCells, Run, find_line_constraints_dp};
+
+/// Runs of a few cells that cycle through the colors, filling about half of the line
+fn runs(line_len: u16, colors: u16) -> Vec<Run> {
+    (0..line_len / 6)
+        .map(|idx| Run::new(Fill::Color(idx % colors + 1), 3))
+        .collect()
+}
+
+/// Line with every seventh cell crossed out, so the runs have to be fit around set cells
+fn cells(line_len: u16) -> LineCells {
+    let fills = (0..line_len).map(|idx| match idx % 7 {
+        6 => Fill::Cross,
+        _ => Fill::Blank,
+    });
+
+    LineCells::from_fills(fills)
+}
+
+fn line_solver(c: &mut Criterion) {
+    let mut group = c.benchmark_group("line_solver");
+
+    for (line_len, colors) in [(30, 1), (100, 4), (300, 8)] {
+        let runs = runs(line_len, colors);
+        let cells = cells(line_len);
+        let automaton = LineAutomaton::new(&runs);
+        let name = format!("{line_len}x{colors}");
+
+        group.bench_with_input(BenchmarkId::new("automaton", &name), &cells, |b, cells| {
+            b.iter(|| automaton.solve(black_box(cells)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("dp", &name), &cells, |b, cells| {
+            b.iter(|| find_line_constraints_dp(&runs, black_box(cells)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, line_solver);
+criterion_main!(benches);
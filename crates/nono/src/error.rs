@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::PuzzleError;
+use crate::{PuzzleError, RuleError, SolverError, generate::GenerateError};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -10,8 +10,25 @@ pub enum Error {
     #[error("Puzzle error: {0}")]
     Puzzle(PuzzleError),
 
+    #[error("Rule errors:\n{}", format_rule_errors(.0))]
+    Rules(Vec<RuleError>),
+
+    #[error("Solver error: {0}")]
+    Solver(SolverError),
+
+    #[error("Generate error: {0}")]
+    Generate(GenerateError),
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 }
 
+fn format_rule_errors(errors: &[RuleError]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("  - {error}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
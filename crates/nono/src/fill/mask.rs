@@ -1,12 +1,14 @@
 use std::ops::Deref;
 
 use bitvec::vec::BitVec;
+use serde::{Deserialize, Serialize};
 
 use crate::{ColorId, Fill};
 
 /// Mask that represents a collection of filles that have been used (1) or not (0)
-/// The
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// The mask is serialized as the list of its fills
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "Vec<Fill>", from = "Vec<Fill>")]
 pub struct FillMask(BitVec);
 
 impl FillMask {
@@ -39,6 +41,23 @@ impl FillMask {
         }
     }
 
+    /// Whether the given fill is in the mask
+    ///
+    /// * `fill`: Fill to look for
+    pub fn contains(&self, fill: Fill) -> bool {
+        Into::<Option<u16>>::into(fill)
+            .and_then(|idx| self.0.get(idx as usize).as_deref().copied())
+            .unwrap_or(false)
+    }
+
+    /// Iterate over the fills of the mask, including crossed out
+    pub fn iter_fills(&self) -> impl Iterator<Item = Fill> {
+        self.0.iter_ones().map(|idx| match idx {
+            0 => Fill::Cross,
+            idx => Fill::Color(idx as ColorId),
+        })
+    }
+
     /// Iterate over the colors of the fill
     pub fn iter_colors(&self) -> impl Iterator<Item = Fill> {
         self.0
@@ -55,3 +74,26 @@ impl Deref for FillMask {
         &self.0
     }
 }
+
+impl FromIterator<Fill> for FillMask {
+    fn from_iter<I: IntoIterator<Item = Fill>>(iter: I) -> Self {
+        let mut mask = Self::new();
+        for fill in iter {
+            mask.add(fill);
+        }
+
+        mask
+    }
+}
+
+impl From<Vec<Fill>> for FillMask {
+    fn from(fills: Vec<Fill>) -> Self {
+        fills.into_iter().collect()
+    }
+}
+
+impl From<FillMask> for Vec<Fill> {
+    fn from(mask: FillMask) -> Self {
+        mask.iter_fills().collect()
+    }
+}
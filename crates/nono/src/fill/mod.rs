@@ -2,12 +2,14 @@ mod mask;
 
 pub use mask::*;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 use crate::ColorId;
 
-#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[derive(
+    Debug, Default, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub enum Fill {
     /// Not yet filled out cell
     #[default]
@@ -66,6 +68,16 @@ impl From<Fill> for Option<u16> {
     }
 }
 
+impl From<Option<u16>> for Fill {
+    fn from(id: Option<u16>) -> Self {
+        match id {
+            None => Fill::Blank,
+            Some(0) => Fill::Cross,
+            Some(col) => Fill::Color(col),
+        }
+    }
+}
+
 impl From<&Fill> for Fill {
     fn from(fill: &Fill) -> Self {
         *fill
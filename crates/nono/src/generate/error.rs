@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GenerateError {
+    #[error("Cannot generate a {rows}x{cols} puzzle")]
+    InvalidSize { rows: u16, cols: u16 },
+
+    #[error("Cannot generate a puzzle with {0} colors")]
+    InvalidColors(u16),
+
+    #[error("Fill density {0} is not in between 0 and 1")]
+    InvalidDensity(f64),
+
+    #[error("No uniquely solvable puzzle was found in {0} attempts")]
+    NotUnique(usize),
+}
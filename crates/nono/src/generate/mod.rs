@@ -0,0 +1,370 @@
+mod error;
+mod rng;
+mod unique;
+
+pub use error::*;
+pub(crate) use rng::*;
+pub use unique::*;
+
+use crate::{
+    Color, ColorId, Error, Fill, Metadata, Nonogram, Position, Puzzle, Result, Rules, Solver,
+    Uniqueness,
+};
+
+/// Number of cells that are changed in a picture before starting over with a fresh picture
+const MAX_REFINEMENTS: usize = 32;
+
+/// Mirror symmetry of a generated picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Left half mirrors the right half
+    Horizontal,
+
+    /// Top half mirrors the bottom half
+    Vertical,
+
+    /// Mirrored both left to right and top to bottom
+    Both,
+
+    /// Picture is the same when turned upside down
+    Rotational,
+}
+
+impl Symmetry {
+    /// All positions that must have the same fill as the given one
+    ///
+    /// * `pos`: Position in the picture
+    /// * `rows`: Number of rows of the picture
+    /// * `cols`: Number of columns of the picture
+    pub fn orbit(&self, pos: Position, rows: u16, cols: u16) -> Vec<Position> {
+        let mirror_row = rows - 1 - pos.row;
+        let mirror_col = cols - 1 - pos.col;
+
+        let mut orbit = match self {
+            Symmetry::Horizontal => vec![pos, Position::new(pos.row, mirror_col)],
+            Symmetry::Vertical => vec![pos, Position::new(mirror_row, pos.col)],
+            Symmetry::Both => vec![
+                pos,
+                Position::new(pos.row, mirror_col),
+                Position::new(mirror_row, pos.col),
+                Position::new(mirror_row, mirror_col),
+            ],
+            Symmetry::Rotational => vec![pos, Position::new(mirror_row, mirror_col)],
+        };
+
+        orbit.sort_by_key(|pos| (pos.row, pos.col));
+        orbit.dedup();
+        orbit
+    }
+}
+
+/// Parameters of a generated nonogram
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    pub rows: u16,
+    pub cols: u16,
+
+    /// Number of colors besides blank
+    pub colors: u16,
+
+    /// Chance of a cell to be colored, in between 0 and 1
+    pub density: f64,
+
+    pub symmetry: Option<Symmetry>,
+
+    /// Seed of the random picture, the same seed always results in the same nonogram
+    pub seed: u64,
+
+    /// Number of pictures to try before giving up on finding a unique one
+    pub max_attempts: usize,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            rows: 10,
+            cols: 10,
+            colors: 1,
+            density: 0.5,
+            symmetry: None,
+            seed: 0,
+            max_attempts: 256,
+        }
+    }
+}
+
+impl GenerateOptions {
+    pub fn new(rows: u16, cols: u16) -> Self {
+        Self {
+            rows,
+            cols,
+            ..Self::default()
+        }
+    }
+
+    pub fn colors(mut self, colors: u16) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    pub fn density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = Some(symmetry);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    fn check(&self) -> std::result::Result<(), GenerateError> {
+        if self.rows == 0 || self.cols == 0 {
+            return Err(GenerateError::InvalidSize {
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+
+        if self.colors == 0 {
+            return Err(GenerateError::InvalidColors(self.colors));
+        }
+
+        if !(0.0..=1.0).contains(&self.density) {
+            return Err(GenerateError::InvalidDensity(self.density));
+        }
+
+        Ok(())
+    }
+}
+
+/// Generate a random nonogram that has a unique solution
+/// Pictures with multiple solutions are changed on the cells where their solutions differ,
+/// until the rules derived from them are uniquely solvable
+/// Fails with a [`GenerateError`] if the options are invalid or no unique picture was found
+///
+/// * `options`: Parameters of the nonogram
+pub fn generate(options: &GenerateOptions) -> Result<Nonogram> {
+    options.check().map_err(Error::Generate)?;
+
+    let mut rng = Rng::new(options.seed);
+    let mut solver = Solver::new();
+
+    let mut picture = random_picture(options, &mut rng);
+
+    for attempt in 0..options.max_attempts {
+        let rules = Rules::from_puzzle(&picture);
+
+        match solver.count_solutions(&rules, 2)? {
+            Uniqueness::Unique(_) => {
+                tracing::info!("Generated a unique puzzle after {} attempts", attempt + 1);
+
+                return Ok(Nonogram {
+                    puzzle: Puzzle::empty(options.rows, options.cols),
+                    rules,
+                    colors: palette(options.colors),
+                    metadata: Metadata::default(),
+                });
+            }
+            Uniqueness::Multiple { first, second, .. } if (attempt + 1) % MAX_REFINEMENTS != 0 => {
+                refine_picture(options, &mut picture, &first, &second, &mut rng);
+            }
+            _ => picture = random_picture(options, &mut rng),
+        }
+    }
+
+    Err(Error::Generate(GenerateError::NotUnique(
+        options.max_attempts,
+    )))
+}
+
+/// Pick a random fill for every cell, the same fill for cells that mirror each other
+fn random_picture(options: &GenerateOptions, rng: &mut Rng) -> Puzzle {
+    let mut picture = Puzzle::empty(options.rows, options.cols);
+
+    for row in 0..options.rows {
+        for col in 0..options.cols {
+            let pos = Position::new(row, col);
+            let orbit = orbit(options, pos);
+
+            // Only pick fills for the first cell of each orbit
+            if orbit[0] != pos {
+                continue;
+            }
+
+            let fill = match rng.next_f64() < options.density {
+                true => Fill::Color(1 + rng.below(options.colors as usize) as ColorId),
+                false => Fill::Blank,
+            };
+
+            for pos in orbit {
+                picture[pos] = fill;
+            }
+        }
+    }
+
+    picture
+}
+
+/// Change a random cell on which two solutions of the picture's rules differ
+fn refine_picture(
+    options: &GenerateOptions,
+    picture: &mut Puzzle,
+    first: &Puzzle,
+    second: &Puzzle,
+    rng: &mut Rng,
+) {
+    let is_colored = |fill: Fill| matches!(fill, Fill::Color(_));
+
+    let mut differences = Vec::new();
+
+    for row in 0..options.rows {
+        for col in 0..options.cols {
+            let pos = Position::new(row, col);
+
+            if first[pos] != second[pos] && (is_colored(first[pos]) || is_colored(second[pos])) {
+                differences.push(pos);
+            }
+        }
+    }
+
+    let Some(&pos) = differences.get(rng.below(differences.len().max(1))) else {
+        return;
+    };
+
+    // Pick any other fill for the cell and its mirrors
+    let fills = options.colors as usize + 1;
+    let current = match picture[pos] {
+        Fill::Color(id) => id as usize,
+        _ => 0,
+    };
+
+    let fill = match (current + 1 + rng.below(fills - 1)) % fills {
+        0 => Fill::Blank,
+        id => Fill::Color(id as ColorId),
+    };
+
+    for pos in orbit(options, pos) {
+        picture[pos] = fill;
+    }
+}
+
+fn orbit(options: &GenerateOptions, pos: Position) -> Vec<Position> {
+    match options.symmetry {
+        Some(symmetry) => symmetry.orbit(pos, options.rows, options.cols),
+        None => vec![pos],
+    }
+}
+
+/// Colors with evenly spread hues
+///
+/// * `colors`: Number of colors
+fn palette(colors: u16) -> Vec<Color> {
+    if colors == 1 {
+        return vec![(0, 0, 0)];
+    }
+
+    (0..colors)
+        .map(|idx| {
+            let hue = idx as f64 / colors as f64 * 6.0;
+            let x = 1.0 - (hue % 2.0 - 1.0).abs();
+
+            let (r, g, b) = match hue as u8 {
+                0 => (1.0, x, 0.0),
+                1 => (x, 1.0, 0.0),
+                2 => (0.0, 1.0, x),
+                3 => (0.0, x, 1.0),
+                4 => (x, 0.0, 1.0),
+                _ => (1.0, 0.0, x),
+            };
+
+            let channel = |value: f64| (value * 200.0 + 40.0) as u8;
+            (channel(r), channel(g), channel(b))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::mono(GenerateOptions::new(8, 8).seed(1))]
+    #[case::colors(GenerateOptions::new(6, 8).colors(3).seed(2))]
+    #[case::sparse(GenerateOptions::new(8, 6).density(0.3).seed(3))]
+    #[case::symmetric(GenerateOptions::new(8, 8).symmetry(Symmetry::Horizontal).seed(4))]
+    #[case::rotational(GenerateOptions::new(7, 7).colors(2).symmetry(Symmetry::Rotational).seed(5))]
+    fn generate_unique(#[case] options: GenerateOptions) {
+        let nonogram = generate(&options).unwrap();
+
+        assert_eq!(nonogram.rules.rows.len(), options.rows as usize);
+        assert_eq!(nonogram.rules.cols.len(), options.cols as usize);
+        assert_eq!(nonogram.colors.len(), options.colors as usize);
+        assert!(nonogram.check().is_ok());
+
+        let uniqueness = Solver::new().count_solutions(&nonogram.rules, 2).unwrap();
+        assert!(uniqueness.is_unique());
+    }
+
+    #[test]
+    fn generate_deterministic() {
+        let options = GenerateOptions::new(8, 8).colors(2).seed(42);
+
+        let first = generate(&options).unwrap();
+        let second = generate(&options).unwrap();
+        let other = generate(&options.clone().seed(43)).unwrap();
+
+        assert_eq!(first.rules.rows, second.rules.rows);
+        assert_eq!(first.rules.cols, second.rules.cols);
+        assert_ne!(first.rules.rows, other.rules.rows);
+    }
+
+    #[rstest]
+    #[case::horizontal(Symmetry::Horizontal, Position::new(1, 0), vec![(1, 0), (1, 3)])]
+    #[case::vertical(Symmetry::Vertical, Position::new(1, 0), vec![(1, 0), (3, 0)])]
+    #[case::both(Symmetry::Both, Position::new(0, 1), vec![(0, 1), (0, 2), (4, 1), (4, 2)])]
+    #[case::rotational(Symmetry::Rotational, Position::new(0, 1), vec![(0, 1), (4, 2)])]
+    #[case::center(Symmetry::Rotational, Position::new(2, 1), vec![(2, 1), (2, 2)])]
+    fn symmetry_orbit(
+        #[case] symmetry: Symmetry,
+        #[case] pos: Position,
+        #[case] expected: Vec<(u16, u16)>,
+    ) {
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(row, col)| Position::new(row, col))
+            .collect();
+
+        assert_eq!(symmetry.orbit(pos, 5, 4), expected);
+    }
+
+    #[test]
+    fn generate_symmetric_rules() {
+        let options = GenerateOptions::new(6, 6).symmetry(Symmetry::Both).seed(7);
+        let nonogram = generate(&options).unwrap();
+
+        let rules = &nonogram.rules;
+        for idx in 0..6 {
+            assert_eq!(rules.rows[idx], rules.rows[5 - idx]);
+            assert_eq!(rules.cols[idx], rules.cols[5 - idx]);
+        }
+    }
+
+    #[rstest]
+    #[case::size(GenerateOptions::new(0, 5))]
+    #[case::colors(GenerateOptions::new(5, 5).colors(0))]
+    #[case::density(GenerateOptions::new(5, 5).density(1.5))]
+    fn generate_invalid(#[case] options: GenerateOptions) {
+        assert!(matches!(generate(&options), Err(Error::Generate(_))));
+    }
+}
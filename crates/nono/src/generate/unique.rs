@@ -0,0 +1,298 @@
+use crate::{
+    Error, Fill, Position, Puzzle, Result, Rules, Solver, Uniqueness, generate::GenerateError,
+};
+
+/// Number of solutions to look for when comparing how ambiguous pictures are
+const AMBIGUITY_LIMIT: usize = 32;
+
+/// Maximum number of cells to try flipping in a single step
+const MAX_FLIP_CANDIDATES: usize = 24;
+
+/// Way to turn an ambiguous picture into a uniquely solvable puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disambiguation {
+    /// Change the fill of at most the given number of cells in the picture
+    Flip { max_flips: usize },
+
+    /// Keep the picture and pre-fill cells of the puzzle instead
+    Givens,
+}
+
+/// Picture with rules that are uniquely solvable
+#[derive(Debug, Clone)]
+pub struct UniquePicture {
+    /// Picture after flipping cells, which is the only solution of the rules
+    pub picture: Puzzle,
+
+    pub rules: Rules,
+
+    /// Cells of the original picture that were flipped
+    pub flipped: Vec<Position>,
+
+    /// Cells that need to be pre-filled from the picture to make the solution unique
+    pub givens: Vec<Position>,
+}
+
+impl UniquePicture {
+    /// Empty puzzle with only the givens filled in and locked, crossing out blank givens
+    pub fn puzzle(&self) -> Puzzle {
+        let mut puzzle = Puzzle::empty(self.picture.rows(), self.picture.cols());
+
+        for &pos in &self.givens {
+            let fill = match self.picture[pos] {
+                Fill::Blank => Fill::Cross,
+                fill => fill,
+            };
+
+            puzzle.set_given(pos, fill);
+        }
+
+        puzzle
+    }
+}
+
+/// Make the rules derived from a picture uniquely solvable, either by flipping as few cells of the
+/// picture as possible or by finding a small set of cells to pre-fill in the puzzle
+/// Fails with [`GenerateError::NotUnique`] if flipping the maximum number of cells is not enough
+///
+/// * `picture`: Picture to derive the rules from, where blank cells are left empty
+/// * `method`: Way to make the solution unique
+pub fn make_unique(picture: &Puzzle, method: Disambiguation) -> Result<UniquePicture> {
+    let picture = without_crosses(picture);
+
+    match method {
+        Disambiguation::Flip { max_flips } => flip_cells(picture, max_flips),
+        Disambiguation::Givens => find_givens(picture),
+    }
+}
+
+/// Greedily flip the cell that leaves the fewest solutions, until a single solution is left
+/// Fails with [`GenerateError::NotUnique`] if no flip within the limit leaves a single solution
+fn flip_cells(mut picture: Puzzle, max_flips: usize) -> Result<UniquePicture> {
+    let mut solver = Solver::new();
+    let mut flipped = Vec::new();
+
+    let not_unique = || Error::Generate(GenerateError::NotUnique(max_flips));
+    let mut uniqueness = count(&mut solver, &picture)?;
+
+    loop {
+        let (first, second) = match uniqueness {
+            Uniqueness::Unique(_) => break,
+            Uniqueness::Multiple { first, second, .. } if flipped.len() < max_flips => {
+                (first, second)
+            }
+            _ => return Err(not_unique()),
+        };
+
+        // Try each cell on which two of the solutions differ
+        let mut best: Option<(usize, Position, Fill, Uniqueness)> = None;
+
+        for pos in spread(differences(&first, &second), MAX_FLIP_CANDIDATES) {
+            let fill = [first[pos], second[pos]]
+                .map(uncrossed)
+                .into_iter()
+                .find(|&fill| fill != picture[pos])
+                .unwrap_or(Fill::Blank);
+
+            let prev = picture[pos];
+            picture[pos] = fill;
+
+            let candidate = count(&mut solver, &picture)?;
+            let solutions = match &candidate {
+                Uniqueness::None => usize::MAX,
+                Uniqueness::Unique(_) => 1,
+                Uniqueness::Multiple { count, .. } => *count,
+            };
+
+            picture[pos] = prev;
+
+            if best.as_ref().is_none_or(|(best, ..)| solutions < *best) {
+                best = Some((solutions, pos, fill, candidate));
+            }
+
+            if solutions == 1 {
+                break;
+            }
+        }
+
+        let Some((_, pos, fill, candidate)) = best else {
+            return Err(not_unique());
+        };
+
+        tracing::debug!("Flipped {pos} to {fill:?}");
+        picture[pos] = fill;
+        flipped.push(pos);
+        uniqueness = candidate;
+    }
+
+    Ok(UniquePicture {
+        rules: Rules::from_puzzle(&picture),
+        picture,
+        flipped,
+        givens: Vec::new(),
+    })
+}
+
+/// Add givens on cells where solutions differ, then drop the ones that turn out to be redundant
+fn find_givens(picture: Puzzle) -> Result<UniquePicture> {
+    let rules = Rules::from_puzzle(&picture);
+
+    let mut solver = Solver::new();
+    let mut unique = UniquePicture {
+        picture,
+        rules,
+        flipped: Vec::new(),
+        givens: Vec::new(),
+    };
+
+    // The picture is one of the solutions, so one of the two solutions differs from it
+    while let Uniqueness::Multiple { first, second, .. } =
+        solver.count_completions(&unique.rules, &unique.puzzle(), 2)?
+    {
+        let other = match differences(&unique.picture, &first).is_empty() {
+            true => second,
+            false => first,
+        };
+
+        let Some(&pos) = differences(&unique.picture, &other).first() else {
+            break;
+        };
+
+        tracing::debug!("Added given at {pos}");
+        unique.givens.push(pos);
+    }
+
+    // Later givens may make earlier ones redundant
+    let mut idx = 0;
+
+    while idx < unique.givens.len() {
+        let pos = unique.givens.remove(idx);
+        let uniqueness = solver.count_completions(&unique.rules, &unique.puzzle(), 2)?;
+
+        if !uniqueness.is_unique() {
+            unique.givens.insert(idx, pos);
+            idx += 1;
+        }
+    }
+
+    Ok(unique)
+}
+
+fn count(solver: &mut Solver, picture: &Puzzle) -> Result<Uniqueness> {
+    solver.count_solutions(&Rules::from_puzzle(picture), AMBIGUITY_LIMIT)
+}
+
+/// Cells on which two puzzles differ, where crossed out and blank cells are the same
+fn differences(first: &Puzzle, second: &Puzzle) -> Vec<Position> {
+    let mut differences = Vec::new();
+
+    for row in 0..first.rows() {
+        for col in 0..first.cols() {
+            let pos = Position::new(row, col);
+
+            if uncrossed(first[pos]) != uncrossed(second[pos]) {
+                differences.push(pos);
+            }
+        }
+    }
+
+    differences
+}
+
+/// Take at most `limit` positions spread evenly over all of them
+fn spread(positions: Vec<Position>, limit: usize) -> Vec<Position> {
+    let len = positions.len();
+    if len <= limit {
+        return positions;
+    }
+
+    (0..limit).map(|idx| positions[idx * len / limit]).collect()
+}
+
+fn uncrossed(fill: Fill) -> Fill {
+    match fill {
+        Fill::Cross => Fill::Blank,
+        fill => fill,
+    }
+}
+
+fn without_crosses(picture: &Puzzle) -> Puzzle {
+    let fills = picture.iter_cells().map(|&fill| uncrossed(fill)).collect();
+
+    Puzzle::new(picture.rows(), picture.cols(), fills).expect("Picture should keep its size")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2, picture};
+    use rstest::rstest;
+
+    fn is_unique(rules: &Rules, puzzle: &Puzzle) -> bool {
+        let mut solver = Solver::new();
+        solver
+            .count_completions(rules, puzzle, 2)
+            .unwrap()
+            .is_unique()
+    }
+
+    #[rstest]
+    #[case::diagonal(2, 2, vec![C1, B, B, C1], 1)]
+    #[case::permutation(3, 3, vec![C1, B, B, B, C1, B, B, B, C1], 2)]
+    #[case::colors(3, 3, vec![C1, B, C2, B, B, B, C2, B, C1], 4)]
+    #[case::unique(2, 2, vec![C1, C1, B, C1], 0)]
+    fn flip_unique(
+        #[case] rows: u16,
+        #[case] cols: u16,
+        #[case] fills: Vec<Fill>,
+        #[case] max_flips: usize,
+    ) {
+        let original = picture(rows, cols, &fills);
+        let unique = make_unique(&original, Disambiguation::Flip { max_flips }).unwrap();
+
+        assert!(unique.flipped.len() <= max_flips);
+        assert!(unique.givens.is_empty());
+        assert!(is_unique(&unique.rules, &Puzzle::empty(rows, cols)));
+        assert_eq!(
+            differences(&original, &unique.picture).len(),
+            unique.flipped.len()
+        );
+    }
+
+    #[rstest]
+    #[case::no_flips(0)]
+    #[case::too_few_flips(2)]
+    fn flip_too_few(#[case] max_flips: usize) {
+        #[rustfmt::skip]
+        let original = picture(4, 4, &[
+            C1, B, B, B,
+            B, C1, B, B,
+            B, B, C1, B,
+            B, B, B, C1,
+        ]);
+        let unique = make_unique(&original, Disambiguation::Flip { max_flips });
+
+        assert!(
+            matches!(unique, Err(Error::Generate(GenerateError::NotUnique(flips))) if flips == max_flips)
+        );
+    }
+
+    #[rstest]
+    #[case::diagonal(2, 2, vec![C1, B, B, C1], 1)]
+    #[case::permutation(3, 3, vec![C1, B, B, B, C1, B, B, B, C1], 2)]
+    #[case::unique(2, 2, vec![C1, C1, B, C1], 0)]
+    fn givens_unique(
+        #[case] rows: u16,
+        #[case] cols: u16,
+        #[case] fills: Vec<Fill>,
+        #[case] expected: usize,
+    ) {
+        let original = picture(rows, cols, &fills);
+        let unique = make_unique(&original, Disambiguation::Givens).unwrap();
+
+        assert_eq!(unique.givens.len(), expected);
+        assert!(unique.flipped.is_empty());
+        assert!(differences(&original, &unique.picture).is_empty());
+        assert!(is_unique(&unique.rules, &unique.puzzle()));
+    }
+}
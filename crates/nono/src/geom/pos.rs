@@ -1,10 +1,12 @@
 use std::fmt;
 use std::ops;
 
+use serde::{Deserialize, Serialize};
+
 use crate::Axis;
 use crate::LinePosition;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Position {
     pub col: u16,
 
@@ -2,12 +2,15 @@
 
 mod error;
 mod fill;
+pub mod generate;
 mod geom;
 mod puzzle;
 mod rules;
 mod run;
 mod solver;
 mod style;
+#[cfg(test)]
+mod testing;
 
 pub use fill::*;
 pub use geom::*;
@@ -19,8 +22,36 @@ pub use style::*;
 pub use error::*;
 pub use solver::*;
 
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Nonogram {
     pub puzzle: Puzzle,
     pub rules: Rules,
     pub colors: Vec<Color>,
+
+    #[serde(default)]
+    pub metadata: Metadata,
+}
+
+/// Information about a nonogram that does not affect solving it
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub copyright: Option<String>,
+}
+
+impl Nonogram {
+    /// Verify that the rules are consistent with the puzzle and its colors, without solving it
+    /// Fails with all [`RuleError`]s that were found
+    pub fn check(&self) -> Result<()> {
+        let mut errors = self.rules.check(self.puzzle.rows(), self.puzzle.cols());
+        errors.extend(self.rules.check_colors(self.colors.len()));
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(Error::Rules(errors)),
+        }
+    }
 }
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Fill, FillMask, Position, Puzzle, PuzzleError};
+
+/// Serialized form of a puzzle, which only lists the cells that are locked or have candidates
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PuzzleData {
+    rows: u16,
+    cols: u16,
+    fills: Vec<Fill>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    locked: Vec<Position>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    candidates: Vec<(Position, FillMask)>,
+}
+
+impl From<Puzzle> for PuzzleData {
+    fn from(puzzle: Puzzle) -> Self {
+        let locked = puzzle.iter_locked().collect();
+        let candidates = puzzle
+            .iter_candidates()
+            .map(|(pos, mask)| (pos, mask.clone()))
+            .collect();
+
+        Self {
+            rows: puzzle.rows(),
+            cols: puzzle.cols(),
+            fills: puzzle.iter_cells().copied().collect(),
+            locked,
+            candidates,
+        }
+    }
+}
+
+impl TryFrom<PuzzleData> for Puzzle {
+    type Error = Error;
+
+    fn try_from(data: PuzzleData) -> Result<Self, Self::Error> {
+        let (rows, cols) = (data.rows, data.cols);
+        let mut puzzle = Puzzle::new(rows, cols, data.fills)?;
+
+        let positions = data
+            .locked
+            .iter()
+            .chain(data.candidates.iter().map(|(pos, _)| pos));
+
+        if let Some(&pos) = positions
+            .into_iter()
+            .find(|pos| pos.row >= rows || pos.col >= cols)
+        {
+            return Err(Error::Puzzle(PuzzleError::OutOfBounds { pos, rows, cols }));
+        }
+
+        for pos in data.locked {
+            puzzle.lock(pos);
+        }
+
+        for (pos, mask) in data.candidates {
+            for fill in mask.iter_fills() {
+                puzzle.add_candidate(pos, fill);
+            }
+        }
+
+        Ok(puzzle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2, X};
+    use crate::{Metadata, Nonogram, Rules};
+    use rstest::rstest;
+
+    fn puzzle(fills: &[Fill]) -> Puzzle {
+        Puzzle::new(2, 3, fills.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn puzzle_round_trip() {
+        let mut expected = puzzle(&[C1, B, X, B, C2, C2]);
+        expected.set_given(Position::new(0, 2), X);
+        expected.add_candidate(Position::new(1, 0), C1);
+        expected.add_candidate(Position::new(1, 0), C2);
+
+        let json = serde_json::to_string(&expected).unwrap();
+        let found: Puzzle = serde_json::from_str(&json).unwrap();
+
+        assert!(found.iter_cells().eq(expected.iter_cells()));
+        assert!(found.iter_locked().eq(expected.iter_locked()));
+        assert!(found.iter_candidates().eq(expected.iter_candidates()));
+    }
+
+    #[test]
+    fn nonogram_round_trip() {
+        let picture = puzzle(&[C1, C1, B, B, C2, C1]);
+        let expected = Nonogram {
+            puzzle: picture.clone(),
+            rules: Rules::from_puzzle(&picture),
+            colors: vec![(0, 0, 0), (255, 0, 0)],
+            metadata: Metadata {
+                title: Some("Flag".to_string()),
+                ..Metadata::default()
+            },
+        };
+
+        let json = serde_json::to_string(&expected).unwrap();
+        let found: Nonogram = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(found.rules.rows, expected.rules.rows);
+        assert_eq!(found.rules.cols, expected.rules.cols);
+        assert_eq!(found.colors, expected.colors);
+        assert_eq!(found.metadata, expected.metadata);
+        assert!(found.check().is_ok());
+    }
+
+    #[rstest]
+    #[case::size(r#"{"rows": 2, "cols": 2, "fills": ["blank_char"]}"#)]
+    #[case::locked(
+        r#"{"rows": 1, "cols": 1, "fills": ["blank_char"], "locked": [{"row": 1, "col": 0}]}"#
+    )]
+    #[case::candidates(r#"{"rows": 1, "cols": 1, "fills": ["blank_char"], "candidates": [[{"row": 0, "col": 3}, [{"Color": 1}]]]}"#)]
+    fn puzzle_invalid(#[case] json: &str) {
+        assert!(serde_json::from_str::<Puzzle>(json).is_err());
+    }
+}
@@ -1,7 +1,12 @@
 use thiserror::Error;
 
+use crate::Position;
+
 #[derive(Debug, Error)]
 pub enum PuzzleError {
     #[error("{rows}x{cols} puzzle has {size} cells (should have {})", rows * cols)]
     SizeMismatch { rows: u16, cols: u16, size: usize },
+
+    #[error("Cell {pos} is outside of the {rows}x{cols} puzzle")]
+    OutOfBounds { pos: Position, rows: u16, cols: u16 },
 }
@@ -1,21 +1,34 @@
+mod data;
 mod error;
 mod find;
 mod iter;
 
+pub(crate) use data::*;
 pub use error::*;
 pub use find::*;
 pub use iter::*;
 
 use std::ops::{Index, IndexMut};
 
-use crate::{Error, Fill, Line, Position, Result};
+use bitvec::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default)]
+use crate::{Error, Fill, FillMask, Line, Position, Result};
+
+/// Grid of cells, whose size is checked again when it is deserialized
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(into = "PuzzleData", try_from = "PuzzleData")]
 pub struct Puzzle {
     // Contents
     rows: u16,
     cols: u16,
     fills: Vec<Fill>,
+
+    /// Cells with a fill that is given by the puzzle and cannot be changed
+    locked: BitVec,
+
+    /// Fills that are noted as possible for each cell, while its fill is not known yet
+    candidates: Vec<FillMask>,
 }
 
 impl Puzzle {
@@ -31,7 +44,15 @@ impl Puzzle {
         }
 
         // Create the puzzle
-        let mut puzzle = Self { rows, cols, fills };
+        let locked = bitvec![0; size];
+        let candidates = vec![FillMask::new(); size];
+        let mut puzzle = Self {
+            rows,
+            cols,
+            fills,
+            locked,
+            candidates,
+        };
 
         // Generate the fill masks
         for row in 0..rows {
@@ -83,6 +104,91 @@ impl Puzzle {
     pub fn fill_cell(&mut self, pos: Position, fill: Fill) {
         self[pos] = fill;
     }
+
+    // Givens
+    /// Set the fill of a cell and lock it, so it cannot be changed anymore
+    ///
+    /// * `pos`: Position of the cell
+    /// * `fill`: Fill that is given by the puzzle
+    pub fn set_given(&mut self, pos: Position, fill: Fill) {
+        self[pos] = fill;
+        self.lock(pos);
+    }
+
+    pub fn lock(&mut self, pos: Position) {
+        let idx = self.index(pos);
+        self.locked.set(idx, true);
+    }
+
+    pub fn unlock(&mut self, pos: Position) {
+        let idx = self.index(pos);
+        self.locked.set(idx, false);
+    }
+
+    pub fn is_locked(&self, pos: Position) -> bool {
+        let idx = self.index(pos);
+        self.locked.get(idx).is_some_and(|locked| *locked)
+    }
+
+    pub fn iter_locked(&self) -> impl Iterator<Item = Position> {
+        let cols = usize::from(self.cols.max(1));
+
+        self.locked
+            .iter_ones()
+            .map(move |idx| Position::new((idx / cols) as u16, (idx % cols) as u16))
+    }
+
+    // Candidates
+    pub fn candidates(&self, pos: Position) -> &FillMask {
+        let idx = self.index(pos);
+        &self.candidates[idx]
+    }
+
+    /// Note a fill as possible for a cell
+    ///
+    /// * `pos`: Position of the cell
+    /// * `fill`: Fill to note, blank fills are ignored
+    pub fn add_candidate(&mut self, pos: Position, fill: Fill) {
+        let idx = self.index(pos);
+        self.candidates[idx].add(fill);
+    }
+
+    /// Remove a fill from the candidates of a cell
+    ///
+    /// * `pos`: Position of the cell
+    /// * `fill`: Fill to remove
+    pub fn remove_candidate(&mut self, pos: Position, fill: Fill) {
+        let idx = self.index(pos);
+        if self.candidates[idx].contains(fill) {
+            self.candidates[idx].remove(fill);
+        }
+    }
+
+    pub fn toggle_candidate(&mut self, pos: Position, fill: Fill) {
+        match self.candidates(pos).contains(fill) {
+            true => self.remove_candidate(pos, fill),
+            false => self.add_candidate(pos, fill),
+        }
+    }
+
+    pub fn clear_candidates(&mut self, pos: Position) {
+        let idx = self.index(pos);
+        self.candidates[idx] = FillMask::new();
+    }
+
+    /// Iterate over the positions of all cells that have candidates noted
+    pub fn iter_candidates(&self) -> impl Iterator<Item = (Position, &FillMask)> {
+        let cols = usize::from(self.cols.max(1));
+
+        self.candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, mask)| mask.any())
+            .map(move |(idx, mask)| {
+                let pos = Position::new((idx / cols) as u16, (idx % cols) as u16);
+                (pos, mask)
+            })
+    }
 }
 
 impl<P> Index<P> for Puzzle
@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Axis, Fill, Line, Rule, RuleError, Rules};
+
+impl Rules {
+    /// Find structural problems in the rules of a puzzle, without solving it
+    ///
+    /// * `rows`: Number of rows in the puzzle
+    /// * `cols`: Number of columns in the puzzle
+    pub fn check(&self, rows: u16, cols: u16) -> Vec<RuleError> {
+        let mut errors = Vec::new();
+
+        // The rules should cover every line exactly once
+        for (axis, rules, expected) in
+            [(Axis::Row, &self.rows, rows), (Axis::Col, &self.cols, cols)]
+        {
+            if rules.len() != expected as usize {
+                errors.push(RuleError::LineCount {
+                    axis,
+                    expected,
+                    found: rules.len(),
+                });
+            }
+        }
+
+        // Each rule should fit in its line
+        for (line, rule) in self.iter_lines() {
+            let line_len = match line {
+                Line::Row(_) => cols,
+                Line::Col(_) => rows,
+            };
+
+            if rule.line_len() != line_len {
+                errors.push(RuleError::LineLength {
+                    line,
+                    rule_len: rule.line_len(),
+                    line_len,
+                });
+            }
+
+            if rule.len() > line_len {
+                errors.push(RuleError::TooLong {
+                    line,
+                    len: rule.len(),
+                    line_len,
+                });
+            }
+        }
+
+        // Rows and columns should fill the same number of cells with each color
+        let row_totals = color_totals(&self.rows);
+        let col_totals = color_totals(&self.cols);
+
+        let fills: BTreeSet<_> = row_totals
+            .keys()
+            .chain(col_totals.keys())
+            .copied()
+            .collect();
+
+        for fill in fills {
+            let row_total = row_totals.get(&fill).copied().unwrap_or_default();
+            let col_total = col_totals.get(&fill).copied().unwrap_or_default();
+
+            if row_total != col_total {
+                errors.push(RuleError::ColorTotals {
+                    fill,
+                    row_total,
+                    col_total,
+                });
+            }
+        }
+
+        errors
+    }
+
+    /// Find runs with colors that are not defined for the puzzle
+    ///
+    /// * `colors`: Number of colors of the puzzle, which are numbered from 1
+    pub fn check_colors(&self, colors: usize) -> Vec<RuleError> {
+        let mut errors = Vec::new();
+
+        for (line, rule) in self.iter_lines() {
+            let mut missing: Vec<_> = rule
+                .runs()
+                .iter()
+                .map(|run| run.fill)
+                .filter(|fill| match fill {
+                    Fill::Color(id) => *id == 0 || *id as usize > colors,
+                    _ => true,
+                })
+                .collect();
+
+            missing.sort();
+            missing.dedup();
+
+            errors.extend(missing.into_iter().map(|fill| RuleError::MissingColor {
+                line,
+                fill,
+                colors,
+            }));
+        }
+
+        errors
+    }
+
+    fn iter_lines(&self) -> impl Iterator<Item = (Line, &Rule)> {
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(r, rule)| (Line::Row(r as u16), rule));
+
+        let cols = self
+            .cols
+            .iter()
+            .enumerate()
+            .map(|(c, rule)| (Line::Col(c as u16), rule));
+
+        rows.chain(cols)
+    }
+}
+
+/// Total number of cells that the rules fill with each fill
+fn color_totals(rules: &[Rule]) -> BTreeMap<Fill, u32> {
+    let mut totals = BTreeMap::new();
+
+    for run in rules.iter().flat_map(|rule| rule.runs()) {
+        *totals.entry(run.fill).or_default() += run.count as u32;
+    }
+
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2};
+    use crate::{Puzzle, Run};
+    use rstest::rstest;
+
+    fn rule(runs: &[(Fill, u16)], line_len: u16) -> Rule {
+        Rule::new(runs.iter().map(|&run| Run::from(run)).collect(), line_len)
+    }
+
+    #[test]
+    fn check_consistent() {
+        let puzzle = Puzzle::new(2, 3, vec![C1, B, C2, C1, C1, B]).unwrap();
+        let rules = Rules::from_puzzle(&puzzle);
+
+        assert_eq!(rules.check(2, 3), vec![]);
+        assert_eq!(rules.check_colors(2), vec![]);
+    }
+
+    #[rstest]
+    #[case::line_count(
+        Rules::new(vec![rule(&[(C1, 1)], 1)], vec![rule(&[(C1, 1)], 1)]),
+        2,
+        1,
+        vec![
+            RuleError::LineCount { axis: Axis::Row, expected: 2, found: 1 },
+            RuleError::LineLength { line: Line::Col(0), rule_len: 1, line_len: 2 },
+        ],
+    )]
+    #[case::line_length(
+        Rules::new(vec![rule(&[(C1, 1)], 2)], vec![rule(&[(C1, 1)], 1)]),
+        1,
+        1,
+        vec![RuleError::LineLength { line: Line::Row(0), rule_len: 2, line_len: 1 }],
+    )]
+    #[case::too_long(
+        Rules::new(
+            vec![rule(&[(C1, 1), (C1, 1)], 2)],
+            vec![rule(&[(C1, 1)], 1), rule(&[(C1, 1)], 1)],
+        ),
+        1,
+        2,
+        vec![RuleError::TooLong { line: Line::Row(0), len: 3, line_len: 2 }],
+    )]
+    #[case::color_totals(
+        Rules::new(vec![rule(&[(C1, 1)], 1)], vec![rule(&[(C2, 1)], 1)]),
+        1,
+        1,
+        vec![
+            RuleError::ColorTotals { fill: C1, row_total: 1, col_total: 0 },
+            RuleError::ColorTotals { fill: C2, row_total: 0, col_total: 1 },
+        ],
+    )]
+    fn check_errors(
+        #[case] rules: Rules,
+        #[case] rows: u16,
+        #[case] cols: u16,
+        #[case] expected: Vec<RuleError>,
+    ) {
+        assert_eq!(rules.check(rows, cols), expected);
+    }
+
+    #[test]
+    fn check_missing_colors() {
+        let rules = Rules::new(
+            vec![rule(&[(C1, 1), (C2, 1), (C2, 1)], 5)],
+            vec![rule(&[(Fill::Color(0), 1)], 1)],
+        );
+
+        let expected = vec![
+            RuleError::MissingColor {
+                line: Line::Row(0),
+                fill: C2,
+                colors: 1,
+            },
+            RuleError::MissingColor {
+                line: Line::Col(0),
+                fill: Fill::Color(0),
+                colors: 1,
+            },
+        ];
+
+        assert_eq!(rules.check_colors(1), expected);
+    }
+}
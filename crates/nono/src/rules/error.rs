@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+use crate::{Axis, Fill, Line};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RuleError {
+    #[error("Puzzle has {expected} {axis:?}s, but {found} {axis:?} rules are given")]
+    LineCount {
+        axis: Axis,
+        expected: u16,
+        found: usize,
+    },
+
+    #[error("Rule of {line:?} is made for {rule_len} cells, but the line has {line_len}")]
+    LineLength {
+        line: Line,
+        rule_len: u16,
+        line_len: u16,
+    },
+
+    #[error("Rule of {line:?} needs at least {len} cells, but the line only has {line_len}")]
+    TooLong { line: Line, len: u16, line_len: u16 },
+
+    #[error("Rule of {line:?} uses {fill:?}, which is not one of the {colors} puzzle colors")]
+    MissingColor {
+        line: Line,
+        fill: Fill,
+        colors: usize,
+    },
+
+    #[error("Rows fill {row_total} cells with {fill:?}, but columns fill {col_total}")]
+    ColorTotals {
+        fill: Fill,
+        row_total: u32,
+        col_total: u32,
+    },
+}
@@ -1,14 +1,20 @@
+mod check;
+mod error;
 mod puzzle;
 mod slice;
 
+pub use error::*;
 pub use puzzle::*;
 pub use slice::*;
 
 use derive_more::Debug;
+use serde::{Deserialize, Serialize};
 
 use crate::{Fill, FillMask, Run, Runs};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Runs of a single line, serialized without the fields that are derived from the runs
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "RuleData", from = "RuleData")]
 pub struct Rule {
     runs: Vec<Run>,
 
@@ -117,6 +123,27 @@ impl Rule {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+struct RuleData {
+    runs: Vec<Run>,
+    line_len: u16,
+}
+
+impl From<RuleData> for Rule {
+    fn from(data: RuleData) -> Self {
+        Rule::new(data.runs, data.line_len)
+    }
+}
+
+impl From<Rule> for RuleData {
+    fn from(rule: Rule) -> Self {
+        Self {
+            runs: rule.runs,
+            line_len: rule.line_len,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
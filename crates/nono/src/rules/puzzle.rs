@@ -1,8 +1,9 @@
 use derive_more::Debug;
+use serde::{Deserialize, Serialize};
 
 use crate::{Puzzle, Rule};
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Rules {
     pub rows: Vec<Rule>,
     pub cols: Vec<Rule>,
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+
+use bitvec::prelude::*;
+
+use crate::{Fill, LineCells, LineConstraint, LineMask, Rule, Run};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Set of automaton states, stored as a bitset
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct States {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl States {
+    fn empty(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+            len,
+        }
+    }
+
+    fn full(len: usize) -> Self {
+        let mut states = Self {
+            words: vec![u64::MAX; len.div_ceil(WORD_BITS)],
+            len,
+        };
+
+        states.truncate();
+        states
+    }
+
+    fn set(&mut self, state: usize) {
+        self.words[state / WORD_BITS] |= 1 << (state % WORD_BITS);
+    }
+
+    fn any(&self) -> bool {
+        self.words.iter().any(|&word| word != 0)
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .any(|(&a, &b)| a & b != 0)
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    fn copy_from(&mut self, other: &Self) {
+        self.words.copy_from_slice(&other.words);
+    }
+
+    fn and_assign(&mut self, other: &Self) {
+        for (a, &b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    /// Add the states of `source` that are in `mask`, moved `by` positions after them
+    fn or_shift_up(&mut self, source: &Self, mask: &Self, by: usize) {
+        let (skip, bits) = (by / WORD_BITS, by % WORD_BITS);
+        let word = |idx: usize| source.words[idx] & mask.words[idx];
+
+        for idx in skip..self.words.len() {
+            let mut shifted = word(idx - skip) << bits;
+            if bits > 0 && idx > skip {
+                shifted |= word(idx - skip - 1) >> (WORD_BITS - bits);
+            }
+
+            self.words[idx] |= shifted;
+        }
+
+        self.truncate();
+    }
+
+    /// Add the states of `source` moved `by` positions before them, that end up in `mask`
+    fn or_shift_down(&mut self, source: &Self, mask: &Self, by: usize) {
+        let (skip, bits) = (by / WORD_BITS, by % WORD_BITS);
+        let n = self.words.len();
+
+        for idx in 0..n.saturating_sub(skip) {
+            let mut shifted = source.words[idx + skip] >> bits;
+            if bits > 0 && idx + skip + 1 < n {
+                shifted |= source.words[idx + skip + 1] << (WORD_BITS - bits);
+            }
+
+            self.words[idx] |= shifted & mask.words[idx];
+        }
+    }
+
+    /// Clear the bits past the last state
+    fn truncate(&mut self) {
+        let rem = self.len % WORD_BITS;
+
+        if let (Some(last), true) = (self.words.last_mut(), rem != 0) {
+            *last &= (1 << rem) - 1;
+        }
+    }
+}
+
+/// Finite automaton that accepts exactly the lines that satisfy a rule
+///
+/// Lines are solved by forward and backward reachability over bitsets of states, which is an
+/// order of magnitude faster than fitting the runs with [`find_line_constraints_dp`] on wide lines
+/// with many colors, see `benches/line_solver.rs`
+///
+/// The states are laid out as `gap 0, run 0 (cell 1..n), gap 1, run 1 (cell 1..n), .., gap m`,
+/// where each state consumes a single cell. A gap state consumes empty cells and loops on itself,
+/// while a run state consumes a single colored cell and moves on to the next state.
+/// The gap in between runs of different colors may be skipped, jumping two states ahead.
+#[derive(Debug, Clone)]
+pub struct LineAutomaton {
+    states: usize,
+
+    /// States that consume empty cells
+    gaps: States,
+
+    /// States that may be followed directly by the state two positions ahead
+    skips: States,
+
+    /// States that consume colored cells, grouped by their color
+    colors: HashMap<Fill, States>,
+
+    start: States,
+    end: States,
+
+    /// All states and no states, which blank cells and unknown colors allow
+    full: States,
+    empty: States,
+}
+
+impl LineAutomaton {
+    pub fn new(runs: &[Run]) -> Self {
+        let runs: Vec<_> = runs.iter().filter(|run| run.count > 0).collect();
+        let states = runs.iter().map(|run| run.count as usize + 1).sum::<usize>() + 1;
+
+        let mut gaps = States::empty(states);
+        let mut skips = States::empty(states);
+        let mut colors: HashMap<Fill, States> = HashMap::new();
+        let mut start = States::empty(states);
+        let mut end = States::empty(states);
+
+        let mut state = 0;
+
+        for (r, run) in runs.iter().enumerate() {
+            // Gap before the run
+            gaps.set(state);
+            state += 1;
+
+            // Cells of the run
+            let color = colors
+                .entry(run.fill)
+                .or_insert_with(|| States::empty(states));
+
+            for _ in 0..run.count {
+                color.set(state);
+                state += 1;
+            }
+
+            // Allow jumping over the gap to a next run of a different color
+            let last = state - 1;
+            if runs.get(r + 1).is_some_and(|next| next.fill != run.fill) {
+                skips.set(last);
+            }
+
+            if r == 0 {
+                start.set(1);
+            }
+            if r + 1 == runs.len() {
+                end.set(last);
+            }
+        }
+
+        // Gap after the last run
+        gaps.set(state);
+        start.set(0);
+        end.set(state);
+
+        Self {
+            states,
+            gaps,
+            skips,
+            colors,
+            start,
+            end,
+            full: States::full(states),
+            empty: States::empty(states),
+        }
+    }
+
+    pub fn from_rule(rule: &Rule) -> Self {
+        Self::new(rule.runs())
+    }
+
+    /// Find which cells may (optional) and must (required) take each fill of a line
+    /// Returns [`None`] if the automaton accepts no line with the given cells
+    ///
+    /// * `cells`: Cells that are currently set in the line
+    pub fn solve(&self, cells: &LineCells) -> Option<HashMap<Fill, LineConstraint>> {
+        let n = cells.len();
+
+        // Only the empty rule fits in an empty line
+        if n == 0 {
+            return (self.states == 1).then(|| self.constraints(&[]));
+        }
+
+        let fills: Vec<_> = (0..n).map(|idx| cells.fill(idx)).collect();
+
+        // Find the states that can be reached from the start..
+        let mut possible = vec![self.empty.clone(); n];
+        possible[0].copy_from(&self.start);
+        possible[0].and_assign(self.allowed(fills[0]));
+
+        for idx in 1..n {
+            let (done, rest) = possible.split_at_mut(idx);
+            self.successors(&done[idx - 1], &mut rest[0]);
+            rest[0].and_assign(self.allowed(fills[idx]));
+        }
+
+        if !possible[n - 1].intersects(&self.end) {
+            return None;
+        }
+
+        // .. and keep those from which the end can be reached
+        let mut backwards = self.end.clone();
+        backwards.and_assign(self.allowed(fills[n - 1]));
+        let mut scratch = self.empty.clone();
+
+        for idx in (0..n).rev() {
+            if idx + 1 < n {
+                self.predecessors(&backwards, &mut scratch);
+                scratch.and_assign(self.allowed(fills[idx]));
+                std::mem::swap(&mut backwards, &mut scratch);
+            }
+
+            possible[idx].and_assign(&backwards);
+        }
+
+        Some(self.constraints(&possible))
+    }
+
+    /// States that may consume the given fill
+    fn allowed(&self, fill: Fill) -> &States {
+        match fill {
+            Fill::Blank => &self.full,
+            Fill::Cross => &self.gaps,
+            color => self.colors.get(&color).unwrap_or(&self.empty),
+        }
+    }
+
+    /// Write the states that follow the given states into `next`
+    fn successors(&self, states: &States, next: &mut States) {
+        next.clear();
+        next.or_shift_up(states, &self.full, 1);
+        next.or_shift_up(states, &self.gaps, 0);
+        next.or_shift_up(states, &self.skips, 2);
+    }
+
+    /// Write the states that precede the given states into `prev`
+    fn predecessors(&self, states: &States, prev: &mut States) {
+        prev.clear();
+        prev.or_shift_down(states, &self.full, 1);
+        prev.or_shift_down(states, &self.gaps, 0);
+        prev.or_shift_down(states, &self.skips, 2);
+    }
+
+    /// Convert the possible states of each cell to the constraints per fill
+    fn constraints(&self, possible: &[States]) -> HashMap<Fill, LineConstraint> {
+        let n = possible.len();
+
+        let optional_of = |states: &States| -> LineMask {
+            possible
+                .iter()
+                .map(|cell| cell.intersects(states))
+                .collect()
+        };
+
+        let mut optionals: Vec<_> = self
+            .colors
+            .iter()
+            .map(|(&fill, states)| (fill, optional_of(states)))
+            .collect();
+        optionals.push((Fill::Cross, optional_of(&self.gaps)));
+
+        // Cells that can only take a single fill are required to take it
+        let mut taken = bitvec![0; n];
+        let mut shared = bitvec![0; n];
+
+        for (_, optional) in &optionals {
+            shared |= taken.clone() & optional;
+            taken |= optional;
+        }
+
+        optionals
+            .into_iter()
+            .map(|(fill, optional)| {
+                let required = optional.clone() & !shared.clone();
+                (fill, LineConstraint { required, optional })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_line_constraints_dp;
+    use crate::testing::{B, C1, C2, C3, X};
+    use rstest::rstest;
+
+    fn runs(runs: &[(Fill, u16)]) -> Vec<Run> {
+        runs.iter().map(|&run| run.into()).collect()
+    }
+
+    #[rstest]
+    #[case(1, 0)]
+    #[case(64, 0)]
+    #[case(64, 1)]
+    #[case(65, 1)]
+    #[case(130, 2)]
+    #[case(130, 64)]
+    #[case(130, 65)]
+    fn shift_round_trip(#[case] len: usize, #[case] by: usize) {
+        let mut states = States::empty(len);
+        for state in (0..len.saturating_sub(by)).step_by(3) {
+            states.set(state);
+        }
+
+        let full = States::full(len);
+        let mut up = States::empty(len);
+        let mut down = States::empty(len);
+
+        up.or_shift_up(&states, &full, by);
+        down.or_shift_down(&up, &full, by);
+
+        assert_eq!(down, states);
+    }
+
+    #[test]
+    fn shift_truncates() {
+        let full = States::full(70);
+        let mut states = States::empty(70);
+        states.set(69);
+
+        let mut shifted = States::empty(70);
+        shifted.or_shift_up(&states, &full, 1);
+        assert!(!shifted.any());
+
+        shifted.or_shift_down(&states, &full, 70);
+        assert!(!shifted.any());
+    }
+
+    #[rstest]
+    #[case::empty_rule(&[], vec![B; 3], true)]
+    #[case::empty_line(&[], vec![], true)]
+    #[case::no_space(&[(C1, 1)], vec![], false)]
+    #[case::fits(&[(C1, 2), (C1, 1)], vec![B; 4], true)]
+    #[case::needs_gap(&[(C1, 2), (C1, 1)], vec![B; 3], false)]
+    #[case::no_gap_needed(&[(C1, 2), (C2, 1)], vec![B; 3], true)]
+    #[case::crossed(&[(C1, 2)], vec![B, X, B], false)]
+    #[case::wrong_color(&[(C1, 1)], vec![C2, B], false)]
+    #[case::order(&[(C1, 1), (C2, 1)], vec![C2, C1], false)]
+    fn solvable(#[case] rule: &[(Fill, u16)], #[case] fills: Vec<Fill>, #[case] expected: bool) {
+        let automaton = LineAutomaton::new(&runs(rule));
+        let cells = LineCells::from_fills(fills);
+
+        assert_eq!(automaton.solve(&cells).is_some(), expected);
+    }
+
+    #[rstest]
+    #[case(&[(C1, 3)], vec![B; 5])]
+    #[case(&[(C1, 1), (C1, 1)], vec![B; 4])]
+    #[case(&[(C1, 2), (C2, 1), (C2, 3)], vec![B; 8])]
+    #[case(&[(C1, 2), (C2, 1), (C1, 3)], vec![B, C1, B, B, B, X, B, B, B, B])]
+    #[case(&[(C3, 1), (C1, 2), (C2, 2), (C1, 1)], vec![B, B, B, C2, B, B, B, B, B])]
+    #[case(&[(C1, 4), (C2, 4), (C1, 4), (C3, 1), (C2, 2)], vec![B; 70])]
+    fn matches_dp(#[case] rule: &[(Fill, u16)], #[case] fills: Vec<Fill>) {
+        let runs = runs(rule);
+        let cells = LineCells::from_fills(fills);
+
+        let constraints = LineAutomaton::new(&runs).solve(&cells).unwrap();
+        let expected = find_line_constraints_dp(&runs, &cells).unwrap();
+
+        assert_eq!(constraints, expected);
+    }
+}
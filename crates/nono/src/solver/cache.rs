@@ -0,0 +1,246 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Fill, LineConstraint, LineMask, Rule, Solver};
+
+/// Default number of line deductions the solver remembers
+pub const DEFAULT_CACHE_CAPACITY: usize = 4096;
+
+/// Rule of a line together with the cells that are set in it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct LineKey {
+    rule: Rule,
+    cells: Vec<(Fill, LineMask)>,
+}
+
+impl LineKey {
+    pub(crate) fn new(rule: &Rule, masks: Option<&HashMap<Fill, LineMask>>) -> Self {
+        // Pack the masks in a fixed order, skipping fills that are no longer set
+        let mut cells: Vec<_> = masks
+            .into_iter()
+            .flatten()
+            .filter(|(_, mask)| mask.any())
+            .map(|(&fill, mask)| (fill, mask.clone()))
+            .collect();
+
+        cells.sort_by_key(|(fill, _)| *fill);
+
+        Self {
+            rule: rule.clone(),
+            cells,
+        }
+    }
+}
+
+/// Constraints of a line, or [`None`] if its cells contradict its rule
+pub(crate) type LineDeduction = Option<HashMap<Fill, LineConstraint>>;
+
+/// Bounded cache of line deductions, which evicts the oldest deductions first
+#[derive(Debug)]
+pub(crate) struct LineCache {
+    capacity: usize,
+
+    entries: HashMap<LineKey, LineDeduction>,
+    order: VecDeque<LineKey>,
+
+    hits: usize,
+    misses: usize,
+}
+
+impl LineCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &LineKey) -> Option<&LineDeduction> {
+        match self.entries.get(key) {
+            Some(deduction) => {
+                self.hits += 1;
+                Some(deduction)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: LineKey, deduction: LineDeduction) {
+        if self.capacity == 0 || self.entries.contains_key(&key) {
+            return;
+        }
+
+        // Make room for the new deduction
+        while self.entries.len() >= self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+
+            self.entries.remove(&oldest);
+        }
+
+        self.order.push_back(key.clone());
+        self.entries.insert(key, deduction);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+
+            self.entries.remove(&oldest);
+        }
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            len: self.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl Default for LineCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+/// Usage of the line deduction cache since it was last cleared
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of line deductions that were found in the cache
+    pub hits: usize,
+
+    /// Number of line deductions that had to be computed
+    pub misses: usize,
+
+    /// Number of line deductions currently in the cache
+    pub len: usize,
+
+    /// Maximum number of line deductions in the cache
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+
+        match total {
+            0 => 0.0,
+            _ => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl Solver {
+    /// Set how many line deductions the solver remembers, where 0 disables the cache
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.cache.set_capacity(capacity);
+    }
+
+    /// Forget all remembered line deductions and reset the cache statistics
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, picture};
+    use crate::{Puzzle, Rules};
+    use bitvec::prelude::*;
+
+    fn key(count: u16) -> LineKey {
+        let rule = Rule::new(vec![(C1, count).into()], 4);
+        LineKey::new(&rule, None)
+    }
+
+    #[test]
+    fn cache_hits_and_misses() {
+        let mut cache = LineCache::new(2);
+
+        assert!(cache.get(&key(1)).is_none());
+        cache.insert(key(1), None);
+        assert!(cache.get(&key(1)).is_some());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.len), (1, 1, 1));
+    }
+
+    #[test]
+    fn cache_evicts_oldest() {
+        let mut cache = LineCache::new(2);
+
+        cache.insert(key(1), None);
+        cache.insert(key(2), None);
+        cache.insert(key(3), None);
+
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(2)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+        assert_eq!(cache.stats().len, 2);
+    }
+
+    #[test]
+    fn cache_disabled() {
+        let mut cache = LineCache::new(0);
+        cache.insert(key(1), None);
+
+        assert!(cache.get(&key(1)).is_none());
+    }
+
+    #[test]
+    fn cache_key_ignores_unset_masks() {
+        let rule = Rule::new(vec![(C1, 1).into()], 2);
+
+        let mut masks = HashMap::new();
+        masks.insert(C1, bitvec![0, 1]);
+        let set = LineKey::new(&rule, Some(&masks));
+
+        masks.insert(Fill::Cross, bitvec![0, 0]);
+        assert_eq!(LineKey::new(&rule, Some(&masks)), set);
+
+        masks.insert(C1, bitvec![0, 0]);
+        assert_eq!(LineKey::new(&rule, Some(&masks)), LineKey::new(&rule, None));
+    }
+
+    #[test]
+    fn solver_reuses_deductions() {
+        // All rows and columns share the same rule
+        let rules = Rules::from_puzzle(&picture(3, 3, &[C1, B, B, B, C1, B, B, B, C1]));
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+
+        let mut puzzle = Puzzle::empty(3, 3);
+        solver.solve_lines(&mut puzzle).unwrap();
+
+        let stats = solver.cache_stats();
+        assert!(stats.hits > 0);
+        assert!(stats.misses > 0);
+    }
+}
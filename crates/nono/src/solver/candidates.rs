@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::{Fill, Line, LineConstraint, Puzzle, Solver};
+
+impl Solver {
+    /// Remove the candidates of each cell that the rule of its row or column no longer allows
+    /// Candidates of cells that are already set are cleared, lines whose rule can no longer be
+    /// satisfied do not narrow anything
+    /// Returns the number of candidates that were removed
+    ///
+    /// * `puzzle`: Puzzle with the candidates to narrow
+    pub fn narrow_candidates(&mut self, puzzle: &mut Puzzle) -> usize {
+        self.sync_masks(puzzle);
+
+        let rows: Vec<_> = (0..puzzle.rows())
+            .map(|row| {
+                self.line_constraints(Line::Row(row), puzzle.cols() as usize)
+                    .ok()
+            })
+            .collect();
+
+        let cols: Vec<_> = (0..puzzle.cols())
+            .map(|col| {
+                self.line_constraints(Line::Col(col), puzzle.rows() as usize)
+                    .ok()
+            })
+            .collect();
+
+        let noted: Vec<_> = puzzle
+            .iter_candidates()
+            .map(|(pos, mask)| (pos, mask.clone()))
+            .collect();
+
+        let mut removed = 0;
+
+        for (pos, mask) in noted {
+            if puzzle[pos] != Fill::Blank {
+                removed += mask.count_ones();
+                puzzle.clear_candidates(pos);
+                continue;
+            }
+
+            let row = rows[pos.row as usize].as_ref();
+            let col = cols[pos.col as usize].as_ref();
+
+            for fill in mask.iter_fills() {
+                if allows(row, fill, pos.col) && allows(col, fill, pos.row) {
+                    continue;
+                }
+
+                tracing::debug!("Ruled out candidate {fill:?} at {pos}");
+                puzzle.remove_candidate(pos, fill);
+                removed += 1;
+            }
+        }
+
+        removed
+    }
+}
+
+/// Whether the constraints of a line allow a fill at an offset
+/// Lines without constraints allow every fill
+fn allows(constraints: Option<&HashMap<Fill, LineConstraint>>, fill: Fill, offset: u16) -> bool {
+    let Some(constraints) = constraints else {
+        return true;
+    };
+
+    constraints.get(&fill).is_some_and(|constraint| {
+        let idx = offset as usize;
+        constraint.optional.get(idx).is_some_and(|bit| *bit)
+            || constraint.required.get(idx).is_some_and(|bit| *bit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2, X, picture};
+    use crate::{Position, Rules};
+    use rstest::rstest;
+
+    fn narrow(pos: Position, noted: &[Fill], puzzle: &mut Puzzle) -> usize {
+        let rules = Rules::from_puzzle(&picture(2, 2, &[C1, C2, B, C2]));
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+
+        for &fill in noted {
+            puzzle.add_candidate(pos, fill);
+        }
+
+        solver.narrow_candidates(puzzle)
+    }
+
+    #[rstest]
+    #[case::wrong_col(Position::new(0, 0), &[C1, C2], &[C1])]
+    #[case::wrong_row(Position::new(1, 0), &[C1, X], &[X])]
+    #[case::full_col(Position::new(0, 1), &[X, C1, C2], &[C2])]
+    #[case::allowed(Position::new(1, 0), &[X], &[X])]
+    fn narrow_candidates(#[case] pos: Position, #[case] noted: &[Fill], #[case] expected: &[Fill]) {
+        let mut puzzle = Puzzle::empty(2, 2);
+        let removed = narrow(pos, noted, &mut puzzle);
+
+        let candidates: Vec<_> = puzzle.candidates(pos).iter_fills().collect();
+        assert_eq!(candidates, expected);
+        assert_eq!(removed, noted.len() - expected.len());
+    }
+
+    #[test]
+    fn narrow_set_cell() {
+        let pos = Position::new(0, 0);
+
+        let mut puzzle = Puzzle::empty(2, 2);
+        puzzle[pos] = C1;
+
+        assert_eq!(narrow(pos, &[C1, C2], &mut puzzle), 2);
+        assert_eq!(puzzle.iter_candidates().count(), 0);
+    }
+}
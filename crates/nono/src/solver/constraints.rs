@@ -1,8 +1,11 @@
-use std::collections::{HashMap, hash_map::Entry};
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    ops::Range,
+};
 
 use bitvec::prelude::*;
 
-use crate::{Fill, Line, LineMask, Run, Solver};
+use crate::{Fill, Line, LineAutomaton, LineKey, LineMask, LineValidation, Run, Solver};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LineConstraint {
@@ -10,6 +13,98 @@ pub struct LineConstraint {
     pub optional: LineMask,
 }
 
+/// Cells of a line that are already filled in, which restrict where runs can be placed
+#[derive(Debug, Clone)]
+pub struct LineCells {
+    len: usize,
+
+    /// Cells that are colored and can therefore not be left empty
+    colored: LineMask,
+
+    /// Cells that are set, grouped by their fill
+    masks: HashMap<Fill, LineMask>,
+}
+
+impl LineCells {
+    pub fn empty(len: usize) -> Self {
+        Self {
+            len,
+            colored: bitvec![0; len],
+            masks: HashMap::new(),
+        }
+    }
+
+    pub fn from_masks(masks: &HashMap<Fill, LineMask>, len: usize) -> Self {
+        let mut colored = bitvec![0; len];
+
+        for (fill, mask) in masks {
+            if matches!(fill, Fill::Color(_)) {
+                colored |= mask;
+            }
+        }
+
+        Self {
+            len,
+            colored,
+            masks: masks.clone(),
+        }
+    }
+
+    pub fn from_fills<I>(fills: I) -> Self
+    where
+        I: IntoIterator<Item = Fill>,
+    {
+        let fills: Vec<_> = fills.into_iter().collect();
+        let len = fills.len();
+
+        let mut masks: HashMap<Fill, LineMask> = HashMap::new();
+
+        for (idx, fill) in fills.into_iter().enumerate() {
+            // Do not include blanks in the masks
+            if matches!(fill, Fill::Blank) {
+                continue;
+            }
+
+            let mask = masks.entry(fill).or_insert_with(|| bitvec![0; len]);
+            mask.set(idx, true);
+        }
+
+        Self::from_masks(&masks, len)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether all cells in the range can take the given fill, i.e. none is set to another fill
+    ///
+    /// * `fill`: Fill to place in the range
+    /// * `range`: Cells to fill
+    fn can_fill(&self, fill: Fill, range: Range<usize>) -> bool {
+        self.masks
+            .iter()
+            .filter(|(other, _)| **other != fill)
+            .all(|(_, mask)| mask[range.clone()].not_any())
+    }
+
+    /// Whether the cell can be left empty, i.e. it is not colored
+    fn can_skip(&self, idx: usize) -> bool {
+        !self.colored[idx]
+    }
+
+    /// Fill that is currently set in the cell, [`Fill::Blank`] if none is
+    pub fn fill(&self, idx: usize) -> Fill {
+        self.masks
+            .iter()
+            .find(|(_, mask)| mask[idx])
+            .map_or(Fill::Blank, |(&fill, _)| fill)
+    }
+}
+
 impl Solver {
     pub fn generate_rule_constraints(&mut self, line: Line) {
         // Find the rule to generate constraints for
@@ -18,115 +113,300 @@ impl Solver {
             return;
         };
 
-        let runs = rule.runs();
-        let line_len = rule.line_len() as usize;
-
-        let left = fit_forwards(runs, line_len);
-        let right = fit_backwards(runs, line_len);
-
-        let mut required_cross = bitvec![1; line_len];
-        let mut optional_cross = bitvec![1; line_len];
-
         // Generate the constraints if they do not yet exists, otherwise return early
-        let constraints = match self.constraints.entry(Line::Row(0)) {
+        let entry = match self.constraints.entry(line) {
             Entry::Occupied(_) => return,
-            Entry::Vacant(v) => v.insert(HashMap::new()),
+            Entry::Vacant(v) => v,
         };
 
-        for color in rule.iter_colors() {
-            // Find all cells that must and may be filled for a given color
-            let (required, optional) = find_filled(runs, line_len, color, &left, &right);
+        let cells = LineCells::empty(rule.line_len() as usize);
+        let constraints = find_line_constraints(rule.runs(), &cells).unwrap_or_default();
 
-            // Eliminate cells that may be filled for all crossed out cells
-            required_cross &= !optional.clone();
-            optional_cross &= !required.clone();
+        for (fill, constraint) in &constraints {
+            tracing::info!("{constraint:?} created for {fill:?} on {line:?}");
+        }
 
-            // Register the must be filled cells for the color
-            let constraint = LineConstraint { required, optional };
-            tracing::info!("{constraint:?} created for {color:?} on {line:?}");
+        entry.insert(constraints);
+    }
 
-            constraints.insert(color, constraint);
+    /// Find the constraints of a line given the cells that are currently set in it
+    /// Fails with [`LineValidation::Invalid`] if the rule of the line can no longer be satisfied,
+    /// or [`LineValidation::MissingRule`] if the line has no rule
+    ///
+    /// * `line`: Line to find the constraints for
+    /// * `line_len`: Length of the line
+    pub fn line_constraints(
+        &mut self,
+        line: Line,
+        line_len: usize,
+    ) -> Result<HashMap<Fill, LineConstraint>, LineValidation> {
+        let rule = self
+            .rules
+            .get(&line)
+            .ok_or(LineValidation::MissingRule(line))?;
+        let masks = self.masks.get(&line);
+
+        // Reuse the deduction for the same rule and cells if it was made before
+        let key = LineKey::new(rule, masks);
+        if let Some(deduction) = self.cache.get(&key) {
+            return deduction.clone().ok_or(LineValidation::Invalid);
         }
 
-        // Finally register the must be crossed out cells
-        let constraint = LineConstraint {
-            required: required_cross,
-            optional: optional_cross,
+        let cells = match masks {
+            Some(masks) => LineCells::from_masks(masks, line_len),
+            None => LineCells::empty(line_len),
+        };
+
+        let deduction = match self.automata.get(&line) {
+            Some(automaton) => automaton.solve(&cells),
+            None => find_line_constraints(rule.runs(), &cells).ok(),
         };
+        self.cache.insert(key, deduction.clone());
+
+        deduction.ok_or(LineValidation::Invalid)
+    }
+}
+
+/// Find which cells may (optional) and must (required) take each fill of a line
+/// Fails with [`LineValidation::Invalid`] if the runs cannot be fit in the line given its
+/// current cells
+///
+/// * `runs`: Runs to fit in the line
+/// * `cells`: Cells that are currently set in the line
+pub(crate) fn find_line_constraints(
+    runs: &[Run],
+    cells: &LineCells,
+) -> Result<HashMap<Fill, LineConstraint>, LineValidation> {
+    LineAutomaton::new(runs)
+        .solve(cells)
+        .ok_or(LineValidation::Invalid)
+}
+
+/// Find the same constraints as [`find_line_constraints`] by fitting the runs from both ends
+/// Returns [`None`] if the runs cannot be fit in the line given its current cells
+///
+/// The [`LineAutomaton`] is much faster, but cannot tell which run covers a cell. The fits stay
+/// to explain deductions in hints by the run that forces them, to verify the automaton against
+/// and to compare its speed with
+///
+/// * `runs`: Runs to fit in the line
+/// * `cells`: Cells that are currently set in the line
+#[doc(hidden)]
+pub fn find_line_constraints_dp(
+    runs: &[Run],
+    cells: &LineCells,
+) -> Option<HashMap<Fill, LineConstraint>> {
+    let m = runs.len();
+    let n = cells.len();
+
+    let left = fit_forwards(runs, cells);
+    let right = fit_backwards(runs, cells);
+
+    // Runs cannot be placed at all
+    if !left[m][n] {
+        return None;
+    }
+
+    let mut constraints = HashMap::new();
+    let mut optional_colors = bitvec![0; n];
+
+    for run in runs {
+        let color = run.fill;
+        if constraints.contains_key(&color) {
+            continue;
+        }
+
+        // Find all cells that must and may be filled for a given color
+        let (required, optional) = find_filled(runs, cells, color, &left, &right);
+        optional_colors |= &optional;
+
+        constraints.insert(color, LineConstraint { required, optional });
+    }
+
+    let optional_cross = find_empty(runs, cells, &left, &right);
+
+    // Cells that can only take a single color must be filled with it
+    let colors: Vec<_> = constraints.keys().copied().collect();
+
+    for &color in &colors {
+        let mut exclusive = constraints[&color].optional.clone() & !optional_cross.clone();
 
-        let cross = Fill::Cross;
-        tracing::info!("{constraint:?} created for {cross:?} on {line:?}");
+        for other in colors.iter().filter(|&&other| other != color) {
+            exclusive &= !constraints[other].optional.clone();
+        }
 
-        constraints.insert(cross, constraint);
+        if let Some(constraint) = constraints.get_mut(&color) {
+            constraint.required |= exclusive;
+        }
     }
+
+    // Cells that cannot take any color must be crossed out
+    let required_cross = optional_cross.clone() & !optional_colors;
+    let constraint = LineConstraint {
+        required: required_cross,
+        optional: optional_cross,
+    };
+
+    constraints.insert(Fill::Cross, constraint);
+
+    Some(constraints)
 }
 
-fn find_filled(
+pub(crate) fn find_filled(
     runs: &[Run],
-    line_len: usize,
+    cells: &LineCells,
     color: Fill,
     prefixes: &[Vec<bool>],
     suffixes: &[Vec<bool>],
 ) -> (LineMask, LineMask) {
-    let m = runs.len();
-    let n = line_len;
+    let n = cells.len();
 
     // Find cells that may be filled by checking for each position
     // if the previous/next fills are a valid prefix/suffix
     let mut must_be_filled = bitvec![0; n];
     let mut maybe_filled = bitvec![0; n];
 
-    for r in 0..m {
-        let run = runs[r];
-
+    for (r, run) in runs.iter().enumerate() {
         // Ignore runs that we're not finding the mask for
         if run.fill != color {
             continue;
         }
 
-        let len = run.count as usize;
-        let mut run_must = bitvec![1; n];
-        let mut has_any = false;
+        if let Some((run_must, run_maybe)) = find_run_filled(runs, r, cells, prefixes, suffixes) {
+            must_be_filled |= run_must;
+            maybe_filled |= run_maybe;
+        }
+    }
+
+    (must_be_filled, maybe_filled)
+}
+
+/// Find the cells that must and may be covered by a single run
+/// Returns [`None`] if the run cannot be placed anywhere
+///
+/// * `runs`: Runs to fit in the line
+/// * `r`: Index of the run to find the cells for
+/// * `cells`: Cells that are currently set in the line
+/// * `prefixes`: Forward fits of the runs
+/// * `suffixes`: Backward fits of the runs
+pub(crate) fn find_run_filled(
+    runs: &[Run],
+    r: usize,
+    cells: &LineCells,
+    prefixes: &[Vec<bool>],
+    suffixes: &[Vec<bool>],
+) -> Option<(LineMask, LineMask)> {
+    let n = cells.len();
+    let len = runs[r].count as usize;
+
+    let mut run_must = bitvec![1; n];
+    let mut run_maybe = bitvec![0; n];
+    let mut has_any = false;
+
+    for (start, &fits) in prefixes[r]
+        .iter()
+        .enumerate()
+        .take(n.saturating_sub(len) + 1)
+    {
+        // Determine where the placed run must end (include gap for same fill)
+        let Some(end) = place_run(runs, r, start, cells) else {
+            continue;
+        };
+
+        // Set all cells in the placement for a valid prefix/suffix pair
+        if fits && suffixes[r + 1][end] {
+            has_any = true;
 
-        for start in 0..=n.saturating_sub(len) {
-            // Determine where the placed run must end (include gap for same fill)
-            let mut end = start + len;
+            let mut mask = bitvec![0; n];
+            mask[start..start + len].fill(true);
 
-            if r + 1 < m && runs[r + 1].fill == run.fill {
-                end += 1;
-            }
+            run_maybe |= &mask;
+            run_must &= &mask;
+        }
+    }
+
+    has_any.then_some((run_must, run_maybe))
+}
+
+/// Find the cells that may be left empty, either in between runs or as a gap between them
+///
+/// * `runs`: Runs to fit in the line
+/// * `cells`: Cells that are currently set in the line
+/// * `prefixes`: Forward fits of the runs
+/// * `suffixes`: Backward fits of the runs
+fn find_empty(
+    runs: &[Run],
+    cells: &LineCells,
+    prefixes: &[Vec<bool>],
+    suffixes: &[Vec<bool>],
+) -> LineMask {
+    let m = runs.len();
+    let n = cells.len();
 
-            // Set all cells in the placement for a valid prefix/suffix pair
-            if prefixes[r][start] && suffixes[r + 1][end.min(n)] {
-                has_any = true;
+    let mut maybe_empty = bitvec![0; n];
+
+    for r in 0..=m {
+        for idx in 0..n {
+            // Skip the cell in between run r - 1 and r
+            if prefixes[r][idx] && suffixes[r][idx + 1] && cells.can_skip(idx) {
+                maybe_empty.set(idx, true);
+            }
 
-                let mut mask = bitvec![0; n];
-                for idx in start..start + len {
-                    mask.set(idx, true);
-                }
+            // Leave the gap right after run r
+            if r == m || !prefixes[r][idx] {
+                continue;
+            }
 
-                maybe_filled |= &mask;
-                run_must &= &mask;
+            let len = runs[r].count as usize;
+            if let Some(end) = place_run(runs, r, idx, cells)
+                && end > idx + len
+                && suffixes[r + 1][end]
+            {
+                maybe_empty.set(idx + len, true);
             }
         }
+    }
 
-        if has_any {
-            must_be_filled |= run_must;
+    maybe_empty
+}
+
+/// Try to place run r at the given start, including the gap to a next run of the same fill
+/// Returns the offset right after the placement if the run fits there
+///
+/// * `runs`: Runs to fit in the line
+/// * `r`: Index of the run to place
+/// * `start`: Offset to place the run at
+/// * `cells`: Cells that are currently set in the line
+fn place_run(runs: &[Run], r: usize, start: usize, cells: &LineCells) -> Option<usize> {
+    let run = runs[r];
+    let n = cells.len();
+
+    let mut end = start + run.count as usize;
+    if end > n || !cells.can_fill(run.fill, start..end) {
+        return None;
+    }
+
+    // Leave a gap for adjacent runs of the same fill
+    if r + 1 < runs.len() && runs[r + 1].fill == run.fill {
+        if end >= n || !cells.can_skip(end) {
+            return None;
         }
+
+        end += 1;
     }
 
-    (must_be_filled, maybe_filled)
+    Some(end)
 }
 
 /// Fit runs in a line going forwards
 ///
 /// * `runs`: Runs to fit in the line
-/// * `line_len`: Length of the line to fit runs in
-fn fit_forwards(runs: &[Run], line_len: usize) -> Vec<Vec<bool>> {
+/// * `cells`: Cells that are currently set in the line
+pub(crate) fn fit_forwards(runs: &[Run], cells: &LineCells) -> Vec<Vec<bool>> {
     let m = runs.len();
-    let n = line_len;
+    let n = cells.len();
 
-    // dp[offset][r]: runs[0..r] fit in cells[0..offset]
+    // dp[r][offset]: runs[0..r] fit in cells[0..offset]
     let mut dp = vec![vec![false; n + 1]; m + 1];
 
     // No runs can always fit
@@ -140,7 +420,7 @@ fn fit_forwards(runs: &[Run], line_len: usize) -> Vec<Vec<bool>> {
             }
 
             // Option 1: leave cell empty
-            if offset < n {
+            if offset < n && cells.can_skip(offset) {
                 dp[r][offset + 1] = true;
             }
 
@@ -150,15 +430,7 @@ fn fit_forwards(runs: &[Run], line_len: usize) -> Vec<Vec<bool>> {
             }
 
             // Option 2: place run r
-            let len = runs[r].count as usize;
-            let mut next = offset + len;
-
-            // Leave a gap for adjacent runs of the same fill
-            if r + 1 < m && runs[r].fill == runs[r + 1].fill {
-                next += 1;
-            }
-
-            if next <= n {
+            if let Some(next) = place_run(runs, r, offset, cells) {
                 dp[r + 1][next] = true;
             }
         }
@@ -170,10 +442,10 @@ fn fit_forwards(runs: &[Run], line_len: usize) -> Vec<Vec<bool>> {
 /// Fit runs in a line going backwards
 ///
 /// * `runs`: Runs to fit in the line
-/// * `line_len`: Length of the line to fit runs in
-fn fit_backwards(runs: &[Run], line_len: usize) -> Vec<Vec<bool>> {
+/// * `cells`: Cells that are currently set in the line
+pub(crate) fn fit_backwards(runs: &[Run], cells: &LineCells) -> Vec<Vec<bool>> {
     let m = runs.len();
-    let n = line_len;
+    let n = cells.len();
 
     // dp[r][offset]: runs[r..m] fit in cells[offset..n]
     let mut dp = vec![vec![false; n + 1]; m + 1];
@@ -183,9 +455,8 @@ fn fit_backwards(runs: &[Run], line_len: usize) -> Vec<Vec<bool>> {
 
     for offset in (0..n).rev() {
         for r in 0..=m {
-            // Another run fits if previous runs already do not fit
-            if dp[r][offset + 1] {
-                // Option 1: leave cell empty
+            // Option 1: leave cell empty
+            if dp[r][offset + 1] && cells.can_skip(offset) {
                 dp[r][offset] = true;
             }
 
@@ -195,15 +466,9 @@ fn fit_backwards(runs: &[Run], line_len: usize) -> Vec<Vec<bool>> {
             }
 
             // Option 2: place run r
-            let len = runs[r].count as usize;
-            let mut next = offset + len;
-
-            // Leave a gap for adjacent runs of the same fill
-            if r + 1 < m && runs[r].fill == runs[r + 1].fill {
-                next += 1;
-            }
-
-            if next <= n && dp[r + 1][next] {
+            if let Some(next) = place_run(runs, r, offset, cells)
+                && dp[r + 1][next]
+            {
                 dp[r][offset] = true;
             }
         }
@@ -252,7 +517,7 @@ mod tests {
         #[case] expected: Vec<Vec<bool>>,
     ) {
         let runs: Vec<Run> = runs.iter().map(|&val| val.into()).collect();
-        let dp = fit_forwards(&runs, line_len);
+        let dp = fit_forwards(&runs, &LineCells::empty(line_len));
 
         assert_eq!(dp, expected);
     }
@@ -275,7 +540,7 @@ mod tests {
         #[case] expected: Vec<Vec<bool>>,
     ) {
         let runs: Vec<Run> = runs.iter().map(|&val| val.into()).collect();
-        let dp = fit_backwards(&runs, line_len);
+        let dp = fit_backwards(&runs, &LineCells::empty(line_len));
 
         assert_eq!(dp, expected);
     }
@@ -296,9 +561,11 @@ mod tests {
     ) {
         let runs: Vec<Run> = runs.iter().map(|&val| val.into()).collect();
 
-        let prefixes = fit_forwards(&runs, line_len);
-        let suffixes = fit_backwards(&runs, line_len);
-        let (mask, _) = find_filled(&runs, line_len, C, &prefixes, &suffixes);
+        let cells = LineCells::empty(line_len);
+
+        let prefixes = fit_forwards(&runs, &cells);
+        let suffixes = fit_backwards(&runs, &cells);
+        let (mask, _) = find_filled(&runs, &cells, C, &prefixes, &suffixes);
 
         assert_eq!(mask, expected);
     }
@@ -318,9 +585,11 @@ mod tests {
     ) {
         let runs: Vec<Run> = runs.iter().map(|&val| val.into()).collect();
 
-        let prefixes = fit_forwards(&runs, line_len);
-        let suffixes = fit_backwards(&runs, line_len);
-        let (_, mask) = find_filled(&runs, line_len, C, &prefixes, &suffixes);
+        let cells = LineCells::empty(line_len);
+
+        let prefixes = fit_forwards(&runs, &cells);
+        let suffixes = fit_backwards(&runs, &cells);
+        let (_, mask) = find_filled(&runs, &cells, C, &prefixes, &suffixes);
 
         assert_eq!(mask, expected);
     }
@@ -0,0 +1,206 @@
+use crate::{Error, Puzzle, Result, Rules, Solver};
+
+/// Whether a set of rules describes a puzzle with a single solution
+#[derive(Debug, Clone)]
+pub enum Uniqueness {
+    /// Rules cannot be satisfied by any puzzle
+    None,
+
+    /// Rules are satisfied by exactly one puzzle
+    Unique(Puzzle),
+
+    /// Rules are satisfied by multiple puzzles, of which `count` were found
+    Multiple {
+        count: usize,
+        first: Puzzle,
+        second: Puzzle,
+    },
+}
+
+impl Uniqueness {
+    pub fn is_unique(&self) -> bool {
+        matches!(self, Uniqueness::Unique(_))
+    }
+}
+
+impl Solver {
+    /// Enumerate the solutions of the rules until the limit is reached
+    /// Note that the limit is at least 2 to be able to tell unique and multiple solutions apart
+    ///
+    /// * `rules`: Rules to find the solutions for, which replace any previously inserted rules
+    /// * `limit`: Maximum number of solutions to find
+    pub fn count_solutions(&mut self, rules: &Rules, limit: usize) -> Result<Uniqueness> {
+        let rows = rules.rows.len() as u16;
+        let cols = rules.cols.len() as u16;
+
+        self.count_completions(rules, &Puzzle::empty(rows, cols), limit)
+    }
+
+    /// Enumerate the solutions of the rules that keep the cells already set in the puzzle
+    /// Note that the limit is at least 2 to be able to tell unique and multiple solutions apart
+    ///
+    /// * `rules`: Rules to find the solutions for, which replace any previously inserted rules
+    /// * `puzzle`: Puzzle with the cells that every solution must keep
+    /// * `limit`: Maximum number of solutions to find
+    pub fn count_completions(
+        &mut self,
+        rules: &Rules,
+        puzzle: &Puzzle,
+        limit: usize,
+    ) -> Result<Uniqueness> {
+        self.clear();
+        self.insert_rules(rules);
+
+        let mut puzzle = puzzle.clone();
+        self.sync_masks(&puzzle);
+        self.enqueue_all(&puzzle);
+
+        let mut solutions = Vec::new();
+        self.enumerate(&mut puzzle, limit.max(2), &mut solutions)?;
+
+        let count = solutions.len();
+        let mut solutions = solutions.into_iter();
+
+        let uniqueness = match (solutions.next(), solutions.next()) {
+            (None, _) => Uniqueness::None,
+            (Some(solution), None) => Uniqueness::Unique(solution),
+            (Some(first), Some(second)) => Uniqueness::Multiple {
+                count,
+                first,
+                second,
+            },
+        };
+
+        Ok(uniqueness)
+    }
+
+    /// Find all solutions that extend the current puzzle until the limit is reached
+    ///
+    /// * `puzzle`: Puzzle to extend
+    /// * `limit`: Maximum number of solutions to find
+    /// * `solutions`: Solutions found so far
+    pub(crate) fn enumerate(
+        &mut self,
+        puzzle: &mut Puzzle,
+        limit: usize,
+        solutions: &mut Vec<Puzzle>,
+    ) -> Result<()> {
+        // Prune the current branch if it leads to a contradiction
+        match self.propagate(puzzle).and_then(|_| self.probe(puzzle)) {
+            Ok(()) => {}
+            Err(Error::Solver(err)) if err.is_contradiction() => return Ok(()),
+            Err(err) => return Err(err),
+        }
+
+        let Some((pos, candidates)) = self.choose_cell(puzzle) else {
+            solutions.push(puzzle.clone());
+            return Ok(());
+        };
+
+        // Each candidate leads to a distinct set of solutions
+        let snapshot = self.snapshot(puzzle);
+
+        for fill in candidates {
+            self.make_guess(puzzle, pos, fill);
+            self.enumerate(puzzle, limit, solutions)?;
+            self.undo_guess(puzzle, &snapshot, pos, fill);
+
+            if solutions.len() >= limit {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2, picture};
+    use crate::{Fill, Rule};
+    use rstest::rstest;
+
+    fn count_picture(rows: u16, cols: u16, fills: &[Fill], limit: usize) -> Uniqueness {
+        let rules = Rules::from_puzzle(&picture(rows, cols, fills));
+
+        Solver::new().count_solutions(&rules, limit).unwrap()
+    }
+
+    fn colored(puzzle: &Puzzle) -> Vec<bool> {
+        puzzle
+            .iter_cells()
+            .map(|fill| matches!(fill, Fill::Color(_)))
+            .collect()
+    }
+
+    #[rstest]
+    #[case::plus(3, 3, vec![B, C1, B, C1, C1, C1, B, C1, B])]
+    #[case::multi_color(2, 2, vec![C1, C2, B, C2])]
+    #[case::beyond_line_logic(4, 4, vec![C1, C1, B, B, B, C1, B, C1, B, B, C1, B, C1, B, B, C1])]
+    fn count_unique(#[case] rows: u16, #[case] cols: u16, #[case] picture: Vec<Fill>) {
+        let uniqueness = count_picture(rows, cols, &picture, 10);
+
+        let Uniqueness::Unique(solution) = uniqueness else {
+            panic!("Expected a unique solution, found {uniqueness:?}");
+        };
+
+        let expected: Vec<_> = picture.iter().map(|&fill| fill != B).collect();
+        assert_eq!(colored(&solution), expected);
+    }
+
+    #[rstest]
+    #[case::diagonal(2, 2, vec![C1, B, B, C1], 10, 2)]
+    #[case::clamped_limit(2, 2, vec![C1, B, B, C1], 1, 2)]
+    #[case::permutations(3, 3, vec![C1, B, B, B, C1, B, B, B, C1], 10, 6)]
+    #[case::limited(3, 3, vec![C1, B, B, B, C1, B, B, B, C1], 4, 4)]
+    fn count_multiple(
+        #[case] rows: u16,
+        #[case] cols: u16,
+        #[case] picture: Vec<Fill>,
+        #[case] limit: usize,
+        #[case] expected: usize,
+    ) {
+        let uniqueness = count_picture(rows, cols, &picture, limit);
+
+        let Uniqueness::Multiple {
+            count,
+            first,
+            second,
+        } = uniqueness
+        else {
+            panic!("Expected multiple solutions, found {uniqueness:?}");
+        };
+
+        assert_eq!(count, expected);
+        assert_ne!(colored(&first), colored(&second));
+    }
+
+    #[test]
+    fn count_completions() {
+        let rules = Rules::from_puzzle(&picture(2, 2, &[C1, B, B, C1]));
+
+        let mut puzzle = Puzzle::empty(2, 2);
+        puzzle[crate::Position::new(0, 0)] = C1;
+
+        let uniqueness = Solver::new()
+            .count_completions(&rules, &puzzle, 10)
+            .unwrap();
+
+        let Uniqueness::Unique(solution) = uniqueness else {
+            panic!("Expected a unique solution, found {uniqueness:?}");
+        };
+        assert_eq!(colored(&solution), vec![true, false, false, true]);
+    }
+
+    #[test]
+    fn count_none() {
+        let rules = Rules::new(
+            vec![Rule::new(vec![(C1, 2).into()], 2)],
+            vec![Rule::new(vec![], 1), Rule::new(vec![(C1, 1).into()], 1)],
+        );
+
+        let uniqueness = Solver::new().count_solutions(&rules, 10).unwrap();
+        assert!(matches!(uniqueness, Uniqueness::None));
+    }
+}
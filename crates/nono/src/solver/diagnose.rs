@@ -0,0 +1,312 @@
+use std::ops::Range;
+
+use crate::{Fill, Run};
+
+/// Kind of mistake that makes a line violate its rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// Cells are colored with a fill that does not occur in the rule
+    UnknownColor,
+
+    /// Colored cells form a run that is longer than the run of the rule
+    RunTooLong,
+
+    /// Colored cells form a closed run that is shorter than the run of the rule
+    RunTooShort,
+
+    /// Colored cells form more runs than the rule contains
+    TooManyRuns,
+
+    /// Line is completely filled in, but contains fewer runs than the rule
+    TooFewRuns,
+
+    /// Colored cells form runs in a different color order than the rule
+    WrongColorOrder,
+
+    /// Two runs of the same color are joined without a gap in between
+    MissingGap,
+}
+
+/// Location and kind of a mistake in a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunViolation {
+    pub kind: ViolationKind,
+
+    /// Offset of the first cell involved in the violation
+    pub start: u16,
+
+    /// Offset right after the last cell involved in the violation
+    pub end: u16,
+
+    /// Index of the run in the rule that the cells conflict with, if any
+    pub run: Option<u16>,
+}
+
+impl RunViolation {
+    pub fn new(kind: ViolationKind, cells: Range<usize>, run: Option<usize>) -> Self {
+        Self {
+            kind,
+            start: cells.start as u16,
+            end: cells.end as u16,
+            run: run.map(|r| r as u16),
+        }
+    }
+
+    pub fn cells(&self) -> Range<u16> {
+        self.start..self.end
+    }
+
+    pub fn contains(&self, offset: u16) -> bool {
+        self.cells().contains(&offset)
+    }
+
+    /// Mirror the violation of a reversed line with the given number of cells and runs
+    fn mirror(self, n: usize, m: usize) -> Self {
+        Self {
+            start: (n - self.end as usize) as u16,
+            end: (n - self.start as usize) as u16,
+            run: self.run.map(|r| (m - 1 - r as usize) as u16),
+            ..self
+        }
+    }
+}
+
+/// Consecutive colored cells of the same fill
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    fill: Fill,
+    start: usize,
+    end: usize,
+}
+
+impl Segment {
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+/// Find where the fills of a line violate the runs of its rule
+/// Returns [`None`] if no specific violation could be located
+///
+/// * `runs`: Runs of the rule of the line
+/// * `fills`: Fills that are currently set in the line
+pub(crate) fn diagnose_line(runs: &[Run], fills: &[Fill]) -> Option<RunViolation> {
+    let runs: Vec<_> = runs.iter().copied().filter(|run| run.count > 0).collect();
+    let segments = find_segments(fills);
+
+    // Colors that do not occur in the rule are always wrong
+    if let Some(segment) = segments
+        .iter()
+        .find(|segment| runs.iter().all(|run| run.fill != segment.fill))
+    {
+        let cells = segment.start..segment.end;
+        return Some(RunViolation::new(ViolationKind::UnknownColor, cells, None));
+    }
+
+    // Runs that are anchored to either end of the line can be matched exactly..
+    if let Some(violation) = diagnose_anchored(&runs, fills, &segments) {
+        return Some(violation);
+    }
+
+    let n = fills.len();
+    let m = runs.len();
+
+    let reversed_runs: Vec<_> = runs.iter().rev().copied().collect();
+    let reversed_fills: Vec<_> = fills.iter().rev().copied().collect();
+    let reversed_segments = find_segments(&reversed_fills);
+
+    if let Some(violation) = diagnose_anchored(&reversed_runs, &reversed_fills, &reversed_segments)
+    {
+        return Some(violation.mirror(n, m));
+    }
+
+    // .. while the others are only matched by their color and length
+    diagnose_unanchored(&runs, fills, &segments)
+}
+
+fn find_segments(fills: &[Fill]) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+
+    for (idx, &fill) in fills.iter().enumerate() {
+        if !matches!(fill, Fill::Color(_)) {
+            continue;
+        }
+
+        match segments.last_mut() {
+            Some(last) if last.fill == fill && last.end == idx => last.end += 1,
+            _ => segments.push(Segment {
+                fill,
+                start: idx,
+                end: idx + 1,
+            }),
+        }
+    }
+
+    segments
+}
+
+/// Match the segments to the runs from the start of the line, as long as no blank cell precedes them
+///
+/// * `runs`: Runs of the rule of the line
+/// * `fills`: Fills that are currently set in the line
+/// * `segments`: Colored segments of the line
+fn diagnose_anchored(runs: &[Run], fills: &[Fill], segments: &[Segment]) -> Option<RunViolation> {
+    let n = fills.len();
+    let mut offset = 0;
+
+    for (r, segment) in segments.iter().enumerate() {
+        // Segment may belong to any run if a blank cell precedes it
+        if fills[offset..segment.start].contains(&Fill::Blank) {
+            return None;
+        }
+
+        let cells = segment.start..segment.end;
+
+        let Some(run) = runs.get(r) else {
+            return Some(RunViolation::new(ViolationKind::TooManyRuns, cells, None));
+        };
+
+        if run.fill != segment.fill {
+            let kind = ViolationKind::WrongColorOrder;
+            return Some(RunViolation::new(kind, cells, Some(r)));
+        }
+
+        let len = run.count as usize;
+
+        if segment.len() > len {
+            let kind = match runs.get(r + 1) {
+                Some(next) if next.fill == run.fill => ViolationKind::MissingGap,
+                _ => ViolationKind::RunTooLong,
+            };
+
+            return Some(RunViolation::new(kind, cells, Some(r)));
+        }
+
+        // Segment can still grow into the blank cell right after it
+        let is_closed = segment.end == n || fills[segment.end] != Fill::Blank;
+        if !is_closed {
+            return None;
+        }
+
+        if segment.len() < len {
+            let kind = ViolationKind::RunTooShort;
+            return Some(RunViolation::new(kind, cells, Some(r)));
+        }
+
+        offset = segment.end;
+    }
+
+    // All cells are set, but not all runs were placed
+    let placed = segments.len();
+    if placed < runs.len() && !fills.contains(&Fill::Blank) {
+        let kind = ViolationKind::TooFewRuns;
+        return Some(RunViolation::new(kind, 0..n, Some(placed)));
+    }
+
+    None
+}
+
+/// Match the segments to the runs by their color and length only
+///
+/// * `runs`: Runs of the rule of the line
+/// * `fills`: Fills that are currently set in the line
+/// * `segments`: Colored segments of the line
+fn diagnose_unanchored(runs: &[Run], fills: &[Fill], segments: &[Segment]) -> Option<RunViolation> {
+    // Segments longer than any run of their color
+    for segment in segments {
+        let longest = runs
+            .iter()
+            .enumerate()
+            .filter(|(_, run)| run.fill == segment.fill)
+            .max_by_key(|(r, run)| (run.count, std::cmp::Reverse(*r)));
+
+        if let Some((r, run)) = longest
+            && segment.len() > run.count as usize
+        {
+            let cells = segment.start..segment.end;
+            return Some(RunViolation::new(ViolationKind::RunTooLong, cells, Some(r)));
+        }
+    }
+
+    // Merge segments of the same color that may still be joined by their blank cells in between
+    let mut groups: Vec<Segment> = Vec::new();
+
+    for &segment in segments {
+        match groups.last_mut() {
+            Some(last)
+                if last.fill == segment.fill
+                    && fills[last.end..segment.start]
+                        .iter()
+                        .all(|&fill| fill == Fill::Blank) =>
+            {
+                last.end = segment.end;
+            }
+            _ => groups.push(segment),
+        }
+    }
+
+    // Each group needs at least one run, in the same order
+    let mut next = 0;
+
+    for (g, group) in groups.iter().enumerate() {
+        let cells = group.start..group.end;
+
+        if g >= runs.len() {
+            return Some(RunViolation::new(ViolationKind::TooManyRuns, cells, None));
+        }
+
+        let Some(r) = (next..runs.len()).find(|&r| runs[r].fill == group.fill) else {
+            let kind = ViolationKind::WrongColorOrder;
+            return Some(RunViolation::new(kind, cells, None));
+        };
+
+        next = r + 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2, C3, X};
+    use rstest::rstest;
+
+    use ViolationKind::*;
+
+    fn runs(runs: &[(Fill, u16)]) -> Vec<Run> {
+        runs.iter().map(|&run| run.into()).collect()
+    }
+
+    #[rstest]
+    #[case::unknown_color(&[(C1, 2)], vec![B, C3, B, B], UnknownColor, 1..2, None)]
+    #[case::too_long(&[(C1, 2)], vec![C1, C1, C1, B], RunTooLong, 0..3, Some(0))]
+    #[case::too_long_right(&[(C1, 1), (C2, 2)], vec![B, B, C2, C2, C2], RunTooLong, 2..5, Some(1))]
+    #[case::too_long_floating(&[(C1, 1), (C1, 3)], vec![B, B, C1, C1, C1, C1, B, B], RunTooLong, 2..6, Some(1))]
+    #[case::too_short(&[(C1, 3)], vec![C1, C1, X, B], RunTooShort, 0..2, Some(0))]
+    #[case::too_short_right(&[(C1, 1), (C2, 3)], vec![B, B, B, X, C2], RunTooShort, 4..5, Some(1))]
+    #[case::too_many(&[(C1, 1)], vec![C1, X, C1, B], TooManyRuns, 2..3, None)]
+    #[case::too_many_floating(&[(C1, 1)], vec![B, C1, X, C1, B], TooManyRuns, 3..4, None)]
+    #[case::too_few(&[(C1, 1), (C1, 1)], vec![X, C1, X, X], TooFewRuns, 0..4, Some(1))]
+    #[case::wrong_order(&[(C1, 1), (C2, 1)], vec![C2, B, B, B], WrongColorOrder, 0..1, Some(0))]
+    #[case::wrong_order_floating(&[(C1, 1), (C2, 1)], vec![B, C2, B, C1, B], WrongColorOrder, 3..4, None)]
+    #[case::missing_gap(&[(C1, 1), (C1, 1)], vec![C1, C1, B, B], MissingGap, 0..2, Some(0))]
+    fn diagnose(
+        #[case] rule: &[(Fill, u16)],
+        #[case] fills: Vec<Fill>,
+        #[case] kind: ViolationKind,
+        #[case] cells: Range<usize>,
+        #[case] run: Option<usize>,
+    ) {
+        let expected = RunViolation::new(kind, cells, run);
+        assert_eq!(diagnose_line(&runs(rule), &fills), Some(expected));
+    }
+
+    #[rstest]
+    #[case::empty(&[(C1, 2)], vec![B; 4])]
+    #[case::partial(&[(C1, 2), (C2, 1)], vec![B, C1, B, C2])]
+    #[case::solved(&[(C1, 2), (C1, 1)], vec![C1, C1, X, C1])]
+    fn diagnose_valid(#[case] rule: &[(Fill, u16)], #[case] fills: Vec<Fill>) {
+        assert_eq!(diagnose_line(&runs(rule), &fills), None);
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use crate::{
+    Budget, DeductionReason, Error, Fill, Nonogram, Puzzle, Result, SolveOptions, Solver,
+    SolverError, SolverStats,
+};
+
+/// Hardest technique that is needed to solve a puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Solvable with overlapping runs, small gaps and completed lines
+    Simple,
+
+    /// Solvable by fully solving one line at a time
+    Lines,
+
+    /// Solvable by probing the fills of single cells
+    Probing,
+
+    /// Requires backtracking on nested guesses
+    Backtracking,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Simple => "Simple",
+            Difficulty::Lines => "Lines",
+            Difficulty::Probing => "Probing",
+            Difficulty::Backtracking => "Backtracking",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DifficultyRating {
+    pub difficulty: Difficulty,
+
+    /// Work the solver did to solve the puzzle
+    pub stats: SolverStats,
+}
+
+impl Solver {
+    /// Rate how difficult a nonogram is by the techniques that are needed to solve it
+    /// Fails with a [`SolverError`] if the nonogram has no solution or more than one
+    ///
+    /// * `nonogram`: Nonogram to rate, starting from the cells that are already set
+    pub fn rate_difficulty(&mut self, nonogram: &Nonogram) -> Result<DifficultyRating> {
+        self.rate_difficulty_with(nonogram, &SolveOptions::default())
+    }
+
+    /// Rate how difficult a nonogram is within the time and step limits of the options
+    /// Fails with [`SolverError::BudgetExhausted`] or [`SolverError::Cancelled`] if the rating
+    /// is stopped early, the guessing option is ignored
+    ///
+    /// * `nonogram`: Nonogram to rate, starting from the cells that are already set
+    /// * `options`: Limits on the rating
+    pub fn rate_difficulty_with(
+        &mut self,
+        nonogram: &Nonogram,
+        options: &SolveOptions,
+    ) -> Result<DifficultyRating> {
+        self.clear();
+        self.insert_rules(&nonogram.rules);
+
+        self.budget = Budget::new(options);
+        let difficulty = self.find_difficulty(nonogram.puzzle.clone());
+        self.budget = Budget::default();
+
+        Ok(DifficultyRating {
+            difficulty: difficulty?,
+            stats: self.stats,
+        })
+    }
+
+    fn find_difficulty(&mut self, mut puzzle: Puzzle) -> Result<Difficulty> {
+        let is_solved = |puzzle: &Puzzle| puzzle.iter_cells().all(|fill| *fill != Fill::Blank);
+
+        // Only apply the simplest deductions
+        let mut simple = puzzle.clone();
+
+        while let Some(deduction) = self.next_deduction(&simple) {
+            if deduction.reason > DeductionReason::GapTooSmall {
+                break;
+            }
+
+            for pos in deduction.positions() {
+                simple[pos] = deduction.fill;
+            }
+        }
+
+        // Then fully solve the lines, which also verifies the simple deductions are valid
+        self.sync_masks(&puzzle);
+        self.frontier.clear();
+        self.enqueue_all(&puzzle);
+        self.propagate(&mut puzzle)?;
+
+        if is_solved(&simple) {
+            return Ok(Difficulty::Simple);
+        }
+        if is_solved(&puzzle) {
+            return Ok(Difficulty::Lines);
+        }
+
+        self.probe(&mut puzzle)?;
+        if is_solved(&puzzle) {
+            return Ok(Difficulty::Probing);
+        }
+
+        // Deductions only hold for unique puzzles, guessing might pick any of the solutions
+        let mut solutions = Vec::new();
+        self.enumerate(&mut puzzle.clone(), 2, &mut solutions)?;
+
+        match solutions.len() {
+            0 => return Err(Error::Solver(SolverError::NoSolution)),
+            1 => {}
+            _ => return Err(Error::Solver(SolverError::NotUnique)),
+        }
+
+        self.search(&mut puzzle)?;
+        Ok(Difficulty::Backtracking)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, PROBING, picture_nonogram};
+    use rstest::rstest;
+
+    fn rate_picture(rows: u16, cols: u16, picture: &[Fill]) -> DifficultyRating {
+        let nonogram = picture_nonogram(rows, cols, picture);
+        Solver::new().rate_difficulty(&nonogram).unwrap()
+    }
+
+    #[rstest]
+    #[case::plus(3, 3, vec![B, C1, B, C1, C1, C1, B, C1, B], Difficulty::Simple)]
+    #[case::corner(3, 3, vec![C1, C1, B, C1, B, B, B, B, B], Difficulty::Lines)]
+    #[case::probing(4, 4, PROBING.to_vec(), Difficulty::Probing)]
+    fn rate_difficulty(
+        #[case] rows: u16,
+        #[case] cols: u16,
+        #[case] picture: Vec<Fill>,
+        #[case] expected: Difficulty,
+    ) {
+        let rating = rate_picture(rows, cols, &picture);
+
+        assert_eq!(rating.difficulty, expected);
+    }
+
+    #[test]
+    fn rate_difficulty_stats() {
+        let line = rate_picture(3, 3, &[B, C1, B, C1, C1, C1, B, C1, B]);
+        assert!(line.stats.rounds > 0);
+        assert_eq!(line.stats.max_depth, 0);
+
+        let probed = rate_picture(4, 4, &PROBING);
+        assert!(probed.stats.probes > 0);
+        assert_eq!(probed.stats.guesses, 0);
+    }
+
+    #[test]
+    fn rate_not_unique() {
+        let nonogram = picture_nonogram(2, 2, &[C1, B, B, C1]);
+        let rated = Solver::new().rate_difficulty(&nonogram);
+
+        assert!(matches!(rated, Err(Error::Solver(SolverError::NotUnique))));
+    }
+
+    #[test]
+    fn rate_budget_exhausted() {
+        let nonogram = picture_nonogram(4, 4, &PROBING);
+        let options = SolveOptions::new().step_limit(3);
+        let rated = Solver::new().rate_difficulty_with(&nonogram, &options);
+
+        assert!(matches!(
+            rated,
+            Err(Error::Solver(SolverError::BudgetExhausted))
+        ));
+    }
+}
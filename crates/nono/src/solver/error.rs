@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+use crate::Line;
+
+#[derive(Debug, Error)]
+pub enum SolverError {
+    #[error("{0:?} cannot satisfy its rule with the cells that are currently set")]
+    Contradiction(Line),
+
+    #[error("Puzzle has no solution")]
+    NoSolution,
+
+    #[error("Puzzle has more than one solution")]
+    NotUnique,
+
+    #[error("Solver ran out of its time or step budget")]
+    BudgetExhausted,
+
+    #[error("Solver was cancelled")]
+    Cancelled,
+}
+
+impl SolverError {
+    /// Whether the error proves the puzzle has no solution, rather than stopping the solver early
+    pub fn is_contradiction(&self) -> bool {
+        matches!(
+            self,
+            SolverError::Contradiction(_) | SolverError::NoSolution
+        )
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+
+use bitvec::bitvec;
+
+use crate::{
+    Fill, Line, LineCells, LineMask, LinePosition, Position, Puzzle, Rule, Solver,
+    find_line_constraints, find_run_filled, fit_backwards, fit_forwards,
+};
+
+/// Logical step that sets one or more cells of a line to the same fill
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deduction {
+    /// Line the deduction was made for
+    pub line: Line,
+
+    /// Cells within the line that are forced to the fill
+    pub cells: LineMask,
+
+    /// Fill that the cells are forced to
+    pub fill: Fill,
+
+    /// Why the cells are forced to the fill
+    pub reason: DeductionReason,
+}
+
+impl Deduction {
+    /// Iterate over the positions of the forced cells
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells
+            .iter_ones()
+            .map(|idx| LinePosition::new(self.line, idx as u16).absolute())
+    }
+}
+
+/// Technique that forces cells to a fill, ordered from simplest to hardest to spot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeductionReason {
+    /// All runs of the line are already filled in, so the remaining cells are crossed out
+    Completed,
+
+    /// Every placement of the run covers the cells, regardless of the cells that are set
+    Overlap { run: u16 },
+
+    /// Cells lie in between crosses that leave too little space for any run
+    GapTooSmall,
+
+    /// The run is pushed against an edge or set cells, so its remaining placements cover the cells
+    Edge { run: u16 },
+
+    /// No run can reach the cells given the cells that are set
+    Unreachable,
+
+    /// Cells cannot take any other fill, given all placements of all runs
+    Exclusion,
+}
+
+impl Solver {
+    /// Find the simplest logical step that can be made from the cells that are currently set
+    /// Returns [`None`] if no cell can be deduced from a single line
+    ///
+    /// * `puzzle`: Puzzle to find the next step for
+    pub fn next_deduction(&self, puzzle: &Puzzle) -> Option<Deduction> {
+        let rows = (0..puzzle.rows()).map(Line::Row);
+        let cols = (0..puzzle.cols()).map(Line::Col);
+
+        rows.chain(cols)
+            .filter_map(|line| {
+                let rule = self.rules.get(&line)?;
+                line_deduction(puzzle, rule, line)
+            })
+            .min_by_key(|deduction| deduction.reason)
+    }
+}
+
+/// Find the simplest deduction for a single line
+///
+/// * `puzzle`: Puzzle the line is part of
+/// * `rule`: Rule of the line
+/// * `line`: Line to find the deduction for
+fn line_deduction(puzzle: &Puzzle, rule: &Rule, line: Line) -> Option<Deduction> {
+    let runs = rule.runs();
+    let fills: Vec<_> = puzzle.iter_line(line).copied().collect();
+    let n = fills.len();
+
+    if rule.line_len() as usize != n {
+        return None;
+    }
+
+    let cells = LineCells::from_fills(fills.iter().copied());
+    let constraints = find_line_constraints(runs, &cells).ok()?;
+
+    // Determine which cells each run covers in all of its placements, with and without set cells
+    // The automaton only knows which fills a cell can take, so the runs are fit one by one here
+    let run_overlaps = |cells: &LineCells| -> Vec<Option<LineMask>> {
+        let left = fit_forwards(runs, cells);
+        let right = fit_backwards(runs, cells);
+
+        (0..runs.len())
+            .map(|r| find_run_filled(runs, r, cells, &left, &right).map(|(must, _)| must))
+            .collect()
+    };
+
+    let overlaps = run_overlaps(&LineCells::empty(n));
+    let edges = run_overlaps(&cells);
+
+    let classify = |idx: usize, fill: Fill| match fill {
+        Fill::Cross if is_completed(rule, &fills) => DeductionReason::Completed,
+        Fill::Cross if is_small_gap(rule, &fills, idx) => DeductionReason::GapTooSmall,
+        Fill::Cross => DeductionReason::Unreachable,
+        _ => {
+            let covers = |masks: &[Option<LineMask>]| {
+                runs.iter()
+                    .zip(masks)
+                    .position(|(run, mask)| {
+                        run.fill == fill && mask.as_ref().is_some_and(|mask| mask[idx])
+                    })
+                    .map(|r| r as u16)
+            };
+
+            if let Some(run) = covers(&overlaps) {
+                DeductionReason::Overlap { run }
+            } else if let Some(run) = covers(&edges) {
+                DeductionReason::Edge { run }
+            } else {
+                DeductionReason::Exclusion
+            }
+        }
+    };
+
+    // Group the blank cells that are forced to a fill by why they are forced
+    let mut groups: BTreeMap<(DeductionReason, Fill), LineMask> = BTreeMap::new();
+
+    for (&fill, constraint) in &constraints {
+        for idx in constraint.required.iter_ones() {
+            if !matches!(fills[idx], Fill::Blank) {
+                continue;
+            }
+
+            let reason = classify(idx, fill);
+            let cells = groups.entry((reason, fill)).or_insert(bitvec![0; n]);
+            cells.set(idx, true);
+        }
+    }
+
+    let ((reason, fill), cells) = groups.into_iter().next()?;
+    Some(Deduction {
+        line,
+        cells,
+        fill,
+        reason,
+    })
+}
+
+/// Whether the colored cells of the line already account for all runs of its rule
+fn is_completed(rule: &Rule, fills: &[Fill]) -> bool {
+    let colored = fills
+        .iter()
+        .filter(|fill| matches!(fill, Fill::Color(_)))
+        .count();
+    let required: usize = rule.runs().iter().map(|run| run.count as usize).sum();
+
+    colored == required
+}
+
+/// Whether the cell lies in between crosses (or edges) that are too close together for any run
+fn is_small_gap(rule: &Rule, fills: &[Fill], idx: usize) -> bool {
+    let Some(min_run) = rule.runs().iter().map(|run| run.count as usize).min() else {
+        return false;
+    };
+
+    let is_cross = |fill: &Fill| matches!(fill, Fill::Cross);
+    let start = fills[..idx]
+        .iter()
+        .rposition(is_cross)
+        .map_or(0, |pos| pos + 1);
+    let end = fills[idx..]
+        .iter()
+        .position(is_cross)
+        .map_or(fills.len(), |pos| idx + pos);
+
+    let gap = &fills[start..end];
+    gap.len() < min_run && gap.iter().all(|fill| matches!(fill, Fill::Blank))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rules;
+    use crate::testing::{B, C1, C2, X};
+    use bitvec::prelude::*;
+    use rstest::rstest;
+
+    fn row_deduction(runs: Vec<(Fill, u16)>, fills: Vec<Fill>) -> Option<Deduction> {
+        let runs = runs.into_iter().map(Into::into).collect();
+        let rule = Rule::new(runs, fills.len() as u16);
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&Rules::new(vec![rule], vec![]));
+
+        let puzzle = Puzzle::new(1, fills.len() as u16, fills).unwrap();
+        solver.next_deduction(&puzzle)
+    }
+
+    #[rstest]
+    #[case::overlap(vec![(C1, 4)], vec![B; 6], C1, bitvec![0, 0, 1, 1, 0, 0], DeductionReason::Overlap { run: 0 })]
+    #[case::overlap_second(vec![(C1, 1), (C2, 3)], vec![B; 5], C2, bitvec![0, 0, 1, 1, 0], DeductionReason::Overlap { run: 1 })]
+    #[case::edge(vec![(C1, 3)], vec![C1, B, B, B, B, B], C1, bitvec![0, 1, 1, 0, 0, 0], DeductionReason::Edge { run: 0 })]
+    #[case::gap(vec![(C1, 2)], vec![B, X, B, B, B], X, bitvec![1, 0, 0, 0, 0], DeductionReason::GapTooSmall)]
+    #[case::completed(vec![(C1, 2)], vec![B, C1, C1, B], X, bitvec![1, 0, 0, 1], DeductionReason::Completed)]
+    #[case::completed_split(vec![(C1, 1), (C1, 1)], vec![C1, B, B, B, C1, B], X, bitvec![0, 1, 1, 1, 0, 1], DeductionReason::Completed)]
+    #[case::empty_rule(vec![], vec![B; 3], X, bitvec![1, 1, 1], DeductionReason::Completed)]
+    fn next_deduction(
+        #[case] runs: Vec<(Fill, u16)>,
+        #[case] fills: Vec<Fill>,
+        #[case] fill: Fill,
+        #[case] cells: BitVec,
+        #[case] reason: DeductionReason,
+    ) {
+        let deduction = row_deduction(runs, fills).unwrap();
+
+        assert_eq!(deduction.line, Line::Row(0));
+        assert_eq!(deduction.fill, fill);
+        assert_eq!(deduction.cells, cells);
+        assert_eq!(deduction.reason, reason);
+    }
+
+    #[test]
+    fn next_deduction_unreachable() {
+        // The single run is anchored at the start, so it cannot reach the end of the line
+        let deduction = row_deduction(vec![(C1, 2)], vec![B, C1, B, B, B]).unwrap();
+
+        assert_eq!(deduction.fill, X);
+        assert_eq!(deduction.cells, bitvec![0, 0, 0, 1, 1]);
+        assert_eq!(deduction.reason, DeductionReason::Unreachable);
+    }
+
+    #[rstest]
+    #[case::solved(vec![(C1, 2)], vec![X, C1, C1, X])]
+    #[case::stuck(vec![(C1, 1)], vec![B, B, B])]
+    #[case::invalid(vec![(C1, 1)], vec![C1, X, C1])]
+    fn no_deduction(#[case] runs: Vec<(Fill, u16)>, #[case] fills: Vec<Fill>) {
+        assert_eq!(row_deduction(runs, fills), None);
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use crate::{
+    Fill, Line, LineCells, LineConstraint, LineMask, Puzzle, Rule, Solver, find_line_constraints,
+};
+
+/// Fills that the cells of a line may and must take, given its rule and the cells that are set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineSolution {
+    len: usize,
+    constraints: HashMap<Fill, LineConstraint>,
+}
+
+impl LineSolution {
+    /// Solve a line from its rule and current cells
+    /// Returns [`None`] if the cells do not match the length of the rule or contradict it
+    ///
+    /// * `rule`: Rule of the line
+    /// * `fills`: Current cells of the line, where blanks are not yet decided
+    pub fn new<I>(rule: &Rule, fills: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Fill>,
+    {
+        let cells = LineCells::from_fills(fills);
+        if cells.len() != rule.line_len() as usize {
+            return None;
+        }
+
+        let constraints = find_line_constraints(rule.runs(), &cells).ok()?;
+
+        Some(Self {
+            len: cells.len(),
+            constraints,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Cells that must take the given fill
+    pub fn required(&self, fill: Fill) -> Option<&LineMask> {
+        self.constraints
+            .get(&fill)
+            .map(|constraint| &constraint.required)
+    }
+
+    /// Cells that may take the given fill
+    pub fn optional(&self, fill: Fill) -> Option<&LineMask> {
+        self.constraints
+            .get(&fill)
+            .map(|constraint| &constraint.optional)
+    }
+
+    /// Fill that the cell is forced to, if it can only take a single one
+    pub fn forced(&self, idx: usize) -> Option<Fill> {
+        self.constraints
+            .iter()
+            .find(|(_, constraint)| constraint.required[idx])
+            .map(|(&fill, _)| fill)
+    }
+
+    /// Iterate over the fill each cell is forced to, if any
+    pub fn iter_forced(&self) -> impl Iterator<Item = Option<Fill>> + '_ {
+        (0..self.len).map(|idx| self.forced(idx))
+    }
+
+    /// Iterate over the fills that the cell may still take
+    pub fn iter_options(&self, idx: usize) -> impl Iterator<Item = Fill> + '_ {
+        self.constraints
+            .iter()
+            .filter(move |(_, constraint)| constraint.optional[idx])
+            .map(|(&fill, _)| fill)
+    }
+
+    pub fn constraints(&self) -> &HashMap<Fill, LineConstraint> {
+        &self.constraints
+    }
+}
+
+impl Solver {
+    /// Solve a line of the puzzle from its rule and the cells that are currently set in it
+    /// Returns [`None`] if the line has no rule or its cells contradict the rule
+    ///
+    /// * `puzzle`: Puzzle the line is part of
+    /// * `line`: Line to solve
+    pub fn solve_line(&self, puzzle: &Puzzle, line: Line) -> Option<LineSolution> {
+        let rule = self.rules.get(&line)?;
+
+        LineSolution::new(rule, puzzle.iter_line(line).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2, X};
+    use rstest::rstest;
+
+    fn solve(runs: Vec<(Fill, u16)>, fills: Vec<Fill>) -> Option<LineSolution> {
+        let runs = runs.into_iter().map(Into::into).collect();
+        let rule = Rule::new(runs, fills.len() as u16);
+
+        LineSolution::new(&rule, fills)
+    }
+
+    #[rstest]
+    #[case::overlap(vec![(C1, 3)], vec![B; 4], vec![None, Some(C1), Some(C1), None])]
+    #[case::empty_rule(vec![], vec![B; 3], vec![Some(X); 3])]
+    #[case::anchored(vec![(C1, 2)], vec![B, C1, B, B, B], vec![None, Some(C1), None, Some(X), Some(X)])]
+    #[case::crossed(vec![(C1, 2)], vec![B, X, B, B], vec![Some(X), Some(X), Some(C1), Some(C1)])]
+    #[case::joined(vec![(C1, 3)], vec![C1, B, C1, B], vec![Some(C1), Some(C1), Some(C1), Some(X)])]
+    #[case::gap(vec![(C1, 1), (C1, 1)], vec![B, C1, B, B], vec![Some(X), Some(C1), Some(X), Some(C1)])]
+    #[case::colors(vec![(C1, 1), (C2, 2)], vec![B, B, B], vec![Some(C1), Some(C2), Some(C2)])]
+    #[case::color_order(vec![(C1, 1), (C2, 1)], vec![B, B, C1, B], vec![Some(X), Some(X), Some(C1), Some(C2)])]
+    #[case::solved(vec![(C1, 1)], vec![X, C1], vec![Some(X), Some(C1)])]
+    fn solve_line(
+        #[case] runs: Vec<(Fill, u16)>,
+        #[case] fills: Vec<Fill>,
+        #[case] expected: Vec<Option<Fill>>,
+    ) {
+        let solution = solve(runs, fills).unwrap();
+        let forced: Vec<_> = solution.iter_forced().collect();
+
+        assert_eq!(forced, expected);
+    }
+
+    #[rstest]
+    #[case::too_many(vec![(C1, 1)], vec![C1, X, C1])]
+    #[case::too_long(vec![(C1, 2)], vec![C1, C1, C1])]
+    #[case::wrong_color(vec![(C1, 1)], vec![B, C2])]
+    #[case::wrong_order(vec![(C1, 1), (C2, 1)], vec![C2, C1])]
+    #[case::no_gap(vec![(C1, 1), (C1, 1)], vec![C1, C1, B])]
+    fn solve_line_contradiction(#[case] runs: Vec<(Fill, u16)>, #[case] fills: Vec<Fill>) {
+        assert_eq!(solve(runs, fills), None);
+    }
+
+    #[test]
+    fn solve_line_length_mismatch() {
+        let rule = Rule::new(vec![(C1, 1).into()], 3);
+
+        assert_eq!(LineSolution::new(&rule, vec![B; 2]), None);
+    }
+
+    #[test]
+    fn solve_line_options() {
+        let solution = solve(vec![(C1, 1), (C2, 1)], vec![B; 3]).unwrap();
+
+        let mut options: Vec<_> = solution.iter_options(1).collect();
+        options.sort();
+
+        assert_eq!(options, vec![X, C1, C2]);
+    }
+}
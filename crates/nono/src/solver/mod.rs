@@ -1,7 +1,34 @@
+mod automaton;
+mod cache;
+mod candidates;
 mod constraints;
+mod count;
+mod diagnose;
+mod difficulty;
+mod error;
+mod hint;
+mod line;
+mod observer;
+mod options;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod propagate;
+mod search;
+mod stats;
 mod validate;
 
+pub use automaton::*;
+pub use cache::*;
 pub use constraints::*;
+pub use count::*;
+pub use diagnose::*;
+pub use difficulty::*;
+pub use error::*;
+pub use hint::*;
+pub use line::*;
+pub use observer::*;
+pub use options::*;
+pub use stats::*;
 pub use validate::*;
 
 use std::{
@@ -10,18 +37,28 @@ use std::{
 };
 
 use bitvec::bitvec;
+use derive_more::Debug;
 
 use crate::{Fill, Line, LineMap, LineMask, LinePosition, Position, Puzzle, Result, Rule, Rules};
 
 #[derive(Debug, Default)]
 pub struct Solver {
     rules: LineMap<Rule>,
+    automata: LineMap<LineAutomaton>,
 
     frontier: VecDeque<Line>,
 
     validations: LineMap<LineValidation>,
     constraints: LineMap<HashMap<Fill, LineConstraint>>,
     masks: LineMap<HashMap<Fill, LineMask>>,
+    cache: LineCache,
+
+    stats: SolverStats,
+    depth: usize,
+    budget: Budget,
+
+    #[debug(skip)]
+    observer: Option<Box<dyn SolverObserver + Send>>,
 }
 
 impl Solver {
@@ -31,23 +68,73 @@ impl Solver {
 
     pub fn clear(&mut self) {
         self.rules.clear();
+        self.automata.clear();
 
         self.frontier.clear();
 
         self.validations.clear();
         self.constraints.clear();
         self.masks.clear();
+
+        self.stats = SolverStats::default();
+        self.depth = 0;
+    }
+
+    /// Solve the puzzle by deducing fills for its lines and guessing when they run out
+    /// Every cell of the puzzle is set once this succeeds
+    /// Fails with a [`SolverError`] if the puzzle has no solution
+    ///
+    /// * `puzzle`: Puzzle to solve, starting from the cells that are already set
+    pub fn solve(&mut self, puzzle: &mut Puzzle) -> Result<()> {
+        self.sync_masks(puzzle);
+        self.frontier.clear();
+        self.enqueue_all(puzzle);
+
+        let searched = self.search(puzzle);
+        self.validate_all(puzzle);
+        self.mark_contradiction(&searched);
+
+        searched
     }
 
-    pub fn solve(&mut self, _puzzle: &mut Puzzle) -> Result<bool> {
-        Ok(true)
+    /// Solve the puzzle only by repeatedly deducing fills for its lines from their rules
+    /// Returns whether all cells of the puzzle could be deduced
+    ///
+    /// * `puzzle`: Puzzle to solve, starting from the cells that are already set
+    pub fn solve_lines(&mut self, puzzle: &mut Puzzle) -> Result<bool> {
+        self.sync_masks(puzzle);
+        self.frontier.clear();
+        self.enqueue_all(puzzle);
+
+        let propagated = self.propagate(puzzle);
+        self.validate_all(puzzle);
+        self.mark_contradiction(&propagated);
+        propagated?;
+
+        let solved = puzzle.iter_cells().all(|fill| !matches!(fill, Fill::Blank));
+        Ok(solved)
+    }
+
+    pub fn stats(&self) -> &SolverStats {
+        &self.stats
     }
 
     pub fn get(&self, line: Line) -> Option<&LineValidation> {
         self.validations.get(&line)
     }
 
-    pub fn update_cell(&mut self, puzzle: &mut Puzzle, pos: Position, fill: Fill) {
+    /// Set the fill of a cell and validate the lines through it
+    /// Returns whether the cell was changed, which is refused for locked cells
+    ///
+    /// * `puzzle`: Puzzle to set the fill in
+    /// * `pos`: Position of the cell
+    /// * `fill`: Fill to set
+    pub fn update_cell(&mut self, puzzle: &mut Puzzle, pos: Position, fill: Fill) -> bool {
+        if puzzle.is_locked(pos) {
+            tracing::warn!("Refused to change the given cell at {pos}");
+            return false;
+        }
+
         // Record the previous fill and set the current
         let prev = puzzle[pos];
         puzzle[pos] = fill;
@@ -72,6 +159,8 @@ impl Solver {
 
         let col_valid = self.validate(puzzle, col);
         self.validations.insert(col, col_valid);
+
+        true
     }
 
     fn set_mask(&mut self, pos: LinePosition, line_len: usize, prev: Fill, curr: Fill) {
@@ -101,11 +190,13 @@ impl Solver {
     pub fn insert_rules(&mut self, rules: &Rules) {
         for (r, rule) in rules.rows.iter().enumerate() {
             let row = Line::Row(r as u16);
+            self.automata.insert(row, LineAutomaton::from_rule(rule));
             self.rules.insert(row, rule.clone());
         }
 
         for (c, rule) in rules.cols.iter().enumerate() {
             let col = Line::Col(c as u16);
+            self.automata.insert(col, LineAutomaton::from_rule(rule));
             self.rules.insert(col, rule.clone());
         }
     }
@@ -120,3 +211,27 @@ impl Index<Line> for Solver {
             .unwrap_or(&LineValidation::Valid)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rules;
+    use crate::testing::{B, C1, picture};
+
+    #[test]
+    fn update_locked_cell() {
+        let rules = Rules::from_puzzle(&picture(1, 4, &[C1, C1, B, B]));
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+
+        let mut puzzle = Puzzle::empty(1, 4);
+        let given = Position::new(0, 1);
+        puzzle.set_given(given, C1);
+
+        assert!(!solver.update_cell(&mut puzzle, given, B));
+        assert!(solver.update_cell(&mut puzzle, Position::new(0, 0), C1));
+        assert_eq!(puzzle[given], C1);
+        assert_eq!(puzzle.iter_locked().collect::<Vec<_>>(), vec![given]);
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::mpsc::Sender;
+
+use crate::{Fill, Line, Position, Solver};
+
+/// Step the solver takes while solving a puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverEvent {
+    /// Line was taken from the frontier to deduce fills for
+    LineDequeued(Line),
+
+    /// Cell was deduced to take a fill
+    CellDeduced { pos: Position, fill: Fill },
+
+    /// Line cannot satisfy its rule with the cells that are currently set
+    Contradiction(Line),
+
+    /// Every fill that was probed for the cell led to a contradiction
+    NoCandidates(Position),
+
+    /// Cell was tentatively set to a fill
+    GuessMade { pos: Position, fill: Fill },
+
+    /// Guess was undone, which reverts all cells that were set since it was made
+    GuessUndone { pos: Position, fill: Fill },
+}
+
+/// Receiver of the steps the solver takes, e.g. to visualise or log them
+pub trait SolverObserver {
+    fn observe(&mut self, event: SolverEvent);
+}
+
+impl SolverObserver for Sender<SolverEvent> {
+    fn observe(&mut self, event: SolverEvent) {
+        // The receiving end may have stopped listening, which should not stop the solver
+        let _ = self.send(event);
+    }
+}
+
+impl Solver {
+    /// Set the observer that is notified of each step the solver takes
+    pub fn set_observer<O>(&mut self, observer: O)
+    where
+        O: SolverObserver + Send + 'static,
+    {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Remove the current observer, if any
+    pub fn take_observer(&mut self) -> Option<Box<dyn SolverObserver + Send>> {
+        self.observer.take()
+    }
+
+    pub(crate) fn notify(&mut self, event: SolverEvent) {
+        if let Some(observer) = self.observer.as_mut() {
+            observer.observe(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, solve_picture};
+    use crate::{Puzzle, Rule, Rules};
+    use std::sync::mpsc::{Receiver, channel};
+
+    fn solve_observed(rows: u16, cols: u16, fills: &[Fill]) -> Receiver<SolverEvent> {
+        let (sender, receiver) = channel();
+        let (solved, _) = solve_picture(rows, cols, fills, |solver, puzzle| {
+            solver.set_observer(sender);
+            solver.solve(puzzle)
+        });
+
+        solved.unwrap();
+        receiver
+    }
+
+    #[test]
+    fn observe_line_logic() {
+        let events: Vec<_> = solve_observed(1, 3, &[C1, C1, B]).try_iter().collect();
+
+        assert_eq!(events[0], SolverEvent::LineDequeued(Line::Row(0)));
+        assert!(events.contains(&SolverEvent::CellDeduced {
+            pos: Position::new(0, 0),
+            fill: C1,
+        }));
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, SolverEvent::GuessMade { .. }))
+        );
+    }
+
+    #[test]
+    fn observe_guesses() {
+        let events: Vec<_> = solve_observed(2, 2, &[C1, B, B, C1]).try_iter().collect();
+
+        let count = |f: fn(&SolverEvent) -> bool| events.iter().filter(|event| f(event)).count();
+        let made = count(|event| matches!(event, SolverEvent::GuessMade { .. }));
+        let undone = count(|event| matches!(event, SolverEvent::GuessUndone { .. }));
+
+        assert!(made > 0);
+        assert!(undone > 0);
+    }
+
+    #[test]
+    fn observe_contradiction() {
+        let rules = Rules::new(
+            vec![Rule::new(vec![(C1, 2).into()], 2)],
+            vec![Rule::new(vec![], 1), Rule::new(vec![(C1, 1).into()], 1)],
+        );
+
+        let (sender, receiver) = channel();
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+        solver.set_observer(sender);
+
+        let mut puzzle = Puzzle::empty(1, 2);
+        assert!(solver.solve(&mut puzzle).is_err());
+
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert!(matches!(events.last(), Some(SolverEvent::Contradiction(_))));
+    }
+
+    #[test]
+    fn observe_probe_contradiction() {
+        // Lines allow every cell to be set either way, but each probe of the corner fails
+        let rules = Rules::new(
+            vec![Rule::new(vec![(C1, 1).into()], 3); 3],
+            vec![
+                Rule::new(vec![(C1, 1).into()], 3),
+                Rule::new(vec![(C1, 1).into()], 3),
+                Rule::new(vec![], 3),
+            ],
+        );
+
+        let (sender, receiver) = channel();
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+        solver.set_observer(sender);
+
+        let mut puzzle = Puzzle::empty(3, 3);
+        assert!(solver.solve(&mut puzzle).is_err());
+
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(
+            events.last(),
+            Some(&SolverEvent::NoCandidates(Position::new(0, 0)))
+        );
+    }
+
+    #[test]
+    fn observe_nothing_after_take() {
+        let (sender, receiver) = channel();
+
+        let mut solver = Solver::new();
+        solver.set_observer(sender);
+        assert!(solver.take_observer().is_some());
+
+        let mut puzzle = Puzzle::empty(1, 1);
+        solver.insert_rules(&Rules::new(vec![], vec![]));
+        solver.solve_lines(&mut puzzle).unwrap();
+
+        assert!(receiver.try_iter().next().is_none());
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{Error, Fill, Puzzle, Result, Solver, SolverError};
+
+/// Shared flag to stop a solver that is running on another thread
+#[derive(Debug, Default, Clone)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits on how long the solver may run and which techniques it may use
+#[derive(Debug, Clone)]
+pub struct SolveOptions {
+    /// Wall-clock time after which the solver gives up
+    pub time_limit: Option<Duration>,
+
+    /// Number of line solves after which the solver gives up
+    pub step_limit: Option<usize>,
+
+    /// Token to stop the solver from another thread
+    pub cancel: Option<CancelToken>,
+
+    /// Whether to guess and backtrack once line deductions and probing run out
+    pub guessing: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        Self {
+            time_limit: None,
+            step_limit: None,
+            cancel: None,
+            guessing: true,
+        }
+    }
+}
+
+impl SolveOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    pub fn step_limit(mut self, limit: usize) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    pub fn cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn guessing(mut self, guessing: bool) -> Self {
+        self.guessing = guessing;
+        self
+    }
+}
+
+/// How a solve with [`SolveOptions`] ended
+#[derive(Debug, Clone)]
+pub enum SolveOutcome {
+    /// All cells of the puzzle are set
+    Solved,
+
+    /// No more cells could be deduced without guessing, which holds the partial grid
+    Stuck(Puzzle),
+
+    /// Puzzle has no solution with the cells that were set beforehand
+    Contradiction,
+
+    /// Time or step limit was reached before the puzzle was solved
+    BudgetExhausted,
+
+    /// Cancel token was triggered before the puzzle was solved
+    Cancelled,
+}
+
+impl SolveOutcome {
+    pub fn is_solved(&self) -> bool {
+        matches!(self, SolveOutcome::Solved)
+    }
+}
+
+/// Remaining budget of the solve that is currently running
+#[derive(Debug, Default)]
+pub(crate) struct Budget {
+    deadline: Option<Instant>,
+    steps_left: Option<usize>,
+    cancel: Option<CancelToken>,
+}
+
+impl Budget {
+    pub(crate) fn new(options: &SolveOptions) -> Self {
+        Self {
+            deadline: options.time_limit.map(|limit| Instant::now() + limit),
+            steps_left: options.step_limit,
+            cancel: options.cancel.clone(),
+        }
+    }
+
+    /// Spend a single step, failing if the budget was used up or the solve was cancelled
+    pub(crate) fn step(&mut self) -> Result<()> {
+        if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+            return Err(Error::Solver(SolverError::Cancelled));
+        }
+
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(Error::Solver(SolverError::BudgetExhausted));
+        }
+
+        if let Some(steps_left) = &mut self.steps_left {
+            if *steps_left == 0 {
+                return Err(Error::Solver(SolverError::BudgetExhausted));
+            }
+
+            *steps_left -= 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl Solver {
+    /// Solve the puzzle within the limits of the options
+    /// Cells that were deduced before the solve stopped are kept, guesses are undone
+    ///
+    /// * `puzzle`: Puzzle to solve, starting from the cells that are already set
+    /// * `options`: Limits on the solve
+    pub fn solve_with(&mut self, puzzle: &mut Puzzle, options: &SolveOptions) -> SolveOutcome {
+        self.budget = Budget::new(options);
+
+        self.sync_masks(puzzle);
+        self.frontier.clear();
+        self.enqueue_all(puzzle);
+
+        let solved = match options.guessing {
+            true => self.search(puzzle),
+            false => self.propagate(puzzle).and_then(|_| self.probe(puzzle)),
+        };
+
+        self.budget = Budget::default();
+        self.validate_all(puzzle);
+
+        match solved {
+            Ok(()) if puzzle.iter_cells().all(|fill| *fill != Fill::Blank) => SolveOutcome::Solved,
+            Ok(()) => SolveOutcome::Stuck(puzzle.clone()),
+            Err(Error::Solver(SolverError::BudgetExhausted)) => SolveOutcome::BudgetExhausted,
+            Err(Error::Solver(SolverError::Cancelled)) => SolveOutcome::Cancelled,
+            Err(err) => {
+                tracing::debug!("Solve stopped: {err}");
+                SolveOutcome::Contradiction
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, PROBING, picture, solve_picture};
+    use rstest::rstest;
+
+    fn solve_options(size: u16, fills: &[Fill], options: &SolveOptions) -> (SolveOutcome, Puzzle) {
+        solve_picture(size, size, fills, |solver, puzzle| {
+            solver.solve_with(puzzle, options)
+        })
+    }
+
+    #[rstest]
+    #[case::unique(4, &PROBING)]
+    #[case::ambiguous(2, &[C1, B, B, C1])]
+    fn solve_with_defaults(#[case] size: u16, #[case] picture: &[Fill]) {
+        let (outcome, puzzle) = solve_options(size, picture, &SolveOptions::default());
+
+        assert!(outcome.is_solved());
+        assert!(puzzle.iter_cells().all(|fill| *fill != B));
+    }
+
+    #[test]
+    fn solve_without_guessing() {
+        let options = SolveOptions::new().guessing(false);
+        let (outcome, puzzle) = solve_options(2, &[C1, B, B, C1], &options);
+
+        assert!(
+            matches!(outcome, SolveOutcome::Stuck(partial) if partial.iter_cells().all(|fill| *fill == B))
+        );
+        assert!(puzzle.iter_cells().all(|fill| *fill == B));
+    }
+
+    #[rstest]
+    #[case::steps(SolveOptions::new().step_limit(3))]
+    #[case::time(SolveOptions::new().time_limit(Duration::ZERO))]
+    fn solve_budget_exhausted(#[case] options: SolveOptions) {
+        let (outcome, _) = solve_options(4, &PROBING, &options);
+        assert!(matches!(outcome, SolveOutcome::BudgetExhausted));
+    }
+
+    #[test]
+    fn solve_cancelled() {
+        let token = CancelToken::new();
+        token.cancel();
+
+        let options = SolveOptions::new().cancel(token);
+        let (outcome, puzzle) = solve_options(4, &PROBING, &options);
+
+        assert!(matches!(outcome, SolveOutcome::Cancelled));
+        assert!(puzzle.iter_cells().all(|fill| *fill == B));
+    }
+
+    #[test]
+    fn solve_keeps_deductions() {
+        // Probing deduces cells before the budget runs out, which are kept
+        let options = SolveOptions::new().step_limit(30);
+        let (outcome, puzzle) = solve_options(4, &PROBING, &options);
+        let expected = picture(4, 4, &PROBING);
+
+        assert!(matches!(outcome, SolveOutcome::BudgetExhausted));
+        assert!(
+            puzzle
+                .iter_cells()
+                .zip(expected.iter_cells())
+                .all(|(fill, expected)| *fill == B || (*fill == Fill::Cross) == (*expected == B))
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::{
+    Error, Fill, Line, LineCells, LineConstraint, LineKey, LineValidation, Puzzle, Result, Solver,
+    SolverError, SolverEvent, find_line_constraints,
+};
+
+/// Minimum number of lines in a sweep to solve them on multiple threads
+const MIN_PARALLEL_LINES: usize = 8;
+
+/// Line of a sweep with the cells it had when the sweep started
+struct SweepLine {
+    line: Line,
+    key: LineKey,
+    cells: LineCells,
+
+    /// Constraints of the line, or the validation of its contradiction, once it is solved
+    deduction: Option<LineResult>,
+
+    /// Whether the deduction was found in this sweep rather than taken from the cache
+    solved: bool,
+}
+
+/// Constraints of a line, or why its cells contradict its rule
+type LineResult = std::result::Result<HashMap<Fill, LineConstraint>, LineValidation>;
+
+impl Solver {
+    /// Deduce fills for the lines on the frontier in sweeps over all rows or all columns
+    /// The lines of a sweep do not share any cells, so they are solved concurrently
+    ///
+    /// * `puzzle`: Puzzle to deduce fills for
+    pub(crate) fn propagate_sweeps(&mut self, puzzle: &mut Puzzle) -> Result<()> {
+        while !self.frontier.is_empty() {
+            self.stats.rounds += 1;
+
+            let mut sweep = self.take_sweep(puzzle)?;
+            self.solve_sweep(&mut sweep);
+
+            // Merge the deductions in the order the lines were on the frontier
+            for SweepLine {
+                line, deduction, ..
+            } in sweep
+            {
+                let constraints = match deduction {
+                    Some(Ok(constraints)) => constraints,
+                    Some(Err(validation)) => {
+                        tracing::debug!("Contradiction found on {line:?}");
+                        self.validations.insert(line, validation);
+                        self.notify(SolverEvent::Contradiction(line));
+                        return Err(Error::Solver(SolverError::Contradiction(line)));
+                    }
+                    None => continue,
+                };
+
+                self.apply_constraints(puzzle, line, &constraints);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take all lines of the same axis as the first line from the frontier
+    ///
+    /// * `puzzle`: Puzzle to take the cells of the lines from
+    fn take_sweep(&mut self, puzzle: &Puzzle) -> Result<Vec<SweepLine>> {
+        let is_row = |line: &Line| matches!(line, Line::Row(_));
+        let Some(axis) = self.frontier.front().map(is_row) else {
+            return Ok(Vec::new());
+        };
+
+        let (lines, rest) = self
+            .frontier
+            .drain(..)
+            .partition(|line| is_row(line) == axis);
+
+        self.frontier = rest;
+
+        let mut sweep = Vec::new();
+
+        for line in lines {
+            // Lines without a rule cannot be deduced from
+            if !self.rules.contains_key(&line) {
+                continue;
+            }
+
+            self.budget.step()?;
+            self.stats.line_solves += 1;
+            self.notify(SolverEvent::LineDequeued(line));
+
+            let rule = &self.rules[&line];
+            let masks = self.masks.get(&line);
+            let line_len = puzzle.line_len(line) as usize;
+
+            let cells = match masks {
+                Some(masks) => LineCells::from_masks(masks, line_len),
+                None => LineCells::empty(line_len),
+            };
+
+            // Reuse the deduction for the same rule and cells if it was made before
+            let key = LineKey::new(rule, masks);
+            let deduction = self
+                .cache
+                .get(&key)
+                .cloned()
+                .map(|deduction| deduction.ok_or(LineValidation::Invalid));
+
+            sweep.push(SweepLine {
+                line,
+                key,
+                cells,
+                deduction,
+                solved: false,
+            });
+        }
+
+        Ok(sweep)
+    }
+
+    /// Find the deductions of all lines in the sweep that were not cached
+    ///
+    /// * `sweep`: Lines to find the deductions for
+    fn solve_sweep(&mut self, sweep: &mut [SweepLine]) {
+        let (rules, automata) = (&self.rules, &self.automata);
+
+        let solve = |sweep_line: &mut SweepLine| {
+            if sweep_line.deduction.is_some() {
+                return;
+            }
+
+            let line = sweep_line.line;
+            let cells = &sweep_line.cells;
+
+            let deduction = match (automata.get(&line), rules.get(&line)) {
+                (Some(automaton), _) => automaton.solve(cells).ok_or(LineValidation::Invalid),
+                (None, Some(rule)) => find_line_constraints(rule.runs(), cells),
+                (None, None) => Err(LineValidation::MissingRule(line)),
+            };
+
+            sweep_line.deduction = Some(deduction);
+            sweep_line.solved = true;
+        };
+
+        if sweep.len() < MIN_PARALLEL_LINES {
+            sweep.iter_mut().for_each(solve);
+        } else {
+            sweep.par_iter_mut().for_each(solve);
+        }
+
+        // Remember the new deductions for later sweeps
+        for sweep_line in sweep.iter().filter(|sweep_line| sweep_line.solved) {
+            if let Some(deduction) = &sweep_line.deduction {
+                self.cache
+                    .insert(sweep_line.key.clone(), deduction.clone().ok());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rules;
+    use crate::testing::{B, C1, C2};
+    use rstest::rstest;
+
+    /// Picture with two colors and a pattern that differs per line
+    fn picture(size: u16, seed: u16) -> Puzzle {
+        let fills = (0..size * size)
+            .map(|idx| match (idx / size * 7 + idx % size * 3 + seed) % 5 {
+                0 | 1 => C1,
+                2 => C2,
+                _ => B,
+            })
+            .collect();
+
+        Puzzle::new(size, size, fills).unwrap()
+    }
+
+    #[rstest]
+    #[case(4, 0)]
+    #[case(12, 1)]
+    #[case(20, 3)]
+    fn sweeps_match_lines(#[case] size: u16, #[case] seed: u16) {
+        let rules = Rules::from_puzzle(&picture(size, seed));
+
+        let propagate = |sweeps: bool| {
+            let mut solver = Solver::new();
+            solver.insert_rules(&rules);
+
+            let mut puzzle = Puzzle::empty(size, size);
+            solver.enqueue_all(&puzzle);
+
+            match sweeps {
+                true => solver.propagate_sweeps(&mut puzzle).unwrap(),
+                false => solver.propagate_lines(&mut puzzle).unwrap(),
+            }
+
+            puzzle.iter_cells().copied().collect::<Vec<_>>()
+        };
+
+        assert_eq!(propagate(true), propagate(false));
+    }
+
+    #[rstest]
+    #[case::sweeps(true)]
+    #[case::lines(false)]
+    fn store_contradiction(#[case] sweeps: bool) {
+        let rules = Rules::from_puzzle(&crate::testing::picture(1, 3, &[C1, B, B]));
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+
+        // Second run of the row is not part of its rule
+        let mut puzzle = crate::testing::picture(1, 3, &[C1, B, C1]);
+        solver.sync_masks(&puzzle);
+        solver.enqueue_all(&puzzle);
+
+        let propagated = match sweeps {
+            true => solver.propagate_sweeps(&mut puzzle),
+            false => solver.propagate_lines(&mut puzzle),
+        };
+
+        assert!(propagated.is_err());
+        assert!(matches!(solver[Line::Row(0)], LineValidation::Invalid));
+    }
+}
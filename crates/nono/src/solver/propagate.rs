@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use crate::{
+    Error, Fill, Line, LineConstraint, LinePosition, LineValidation, Position, Puzzle, Result,
+    Solver, SolverError, SolverEvent,
+};
+
+impl Solver {
+    /// Deduce fills for the lines on the frontier until no more lines are left
+    ///
+    /// * `puzzle`: Puzzle to deduce fills for
+    pub(crate) fn propagate(&mut self, puzzle: &mut Puzzle) -> Result<()> {
+        #[cfg(feature = "parallel")]
+        return self.propagate_sweeps(puzzle);
+
+        #[cfg(not(feature = "parallel"))]
+        self.propagate_lines(puzzle)
+    }
+
+    /// Deduce fills for the lines on the frontier one by one
+    ///
+    /// * `puzzle`: Puzzle to deduce fills for
+    pub(crate) fn propagate_lines(&mut self, puzzle: &mut Puzzle) -> Result<()> {
+        let mut round_left = 0;
+
+        while let Some(line) = self.frontier.pop_front() {
+            // Start a new round once all lines of the previous round were worked through
+            if round_left == 0 {
+                self.stats.rounds += 1;
+                round_left = self.frontier.len() + 1;
+            }
+            round_left -= 1;
+
+            // Lines without a rule cannot be deduced from
+            if !self.rules.contains_key(&line) {
+                continue;
+            }
+
+            self.budget.step()?;
+            self.stats.line_solves += 1;
+            self.notify(SolverEvent::LineDequeued(line));
+
+            let line_len = puzzle.line_len(line) as usize;
+            let constraints = match self.line_constraints(line, line_len) {
+                Ok(constraints) => constraints,
+                Err(validation) => {
+                    tracing::debug!("Contradiction found on {line:?}");
+                    self.validations.insert(line, validation);
+                    self.notify(SolverEvent::Contradiction(line));
+                    return Err(Error::Solver(SolverError::Contradiction(line)));
+                }
+            };
+
+            self.apply_constraints(puzzle, line, &constraints);
+        }
+
+        Ok(())
+    }
+
+    /// Set all blank cells of a line that are required to take a single fill
+    ///
+    /// * `puzzle`: Puzzle to deduce fills for
+    /// * `line`: Line the constraints were found for
+    /// * `constraints`: Constraints of the line given its current cells
+    pub(crate) fn apply_constraints(
+        &mut self,
+        puzzle: &mut Puzzle,
+        line: Line,
+        constraints: &HashMap<Fill, LineConstraint>,
+    ) {
+        for (&fill, constraint) in constraints {
+            for idx in constraint.required.iter_ones() {
+                let pos = LinePosition::new(line, idx as u16);
+
+                if matches!(puzzle[pos], Fill::Blank) {
+                    tracing::debug!("Deduced {fill:?} at {pos:?}");
+                    self.deduce_cell(puzzle, pos, fill);
+                }
+            }
+        }
+    }
+
+    /// Set a deduced fill and revisit the line that crosses it
+    ///
+    /// * `puzzle`: Puzzle to set the fill in
+    /// * `pos`: Position of the cell within the line it was deduced from
+    /// * `fill`: Fill to set
+    fn deduce_cell(&mut self, puzzle: &mut Puzzle, pos: LinePosition, fill: Fill) {
+        let (row_pos, col_pos) = self.set_cell(puzzle, pos.absolute(), fill);
+        self.notify(SolverEvent::CellDeduced {
+            pos: pos.absolute(),
+            fill,
+        });
+
+        let cross = match pos.line {
+            Line::Row(_) => col_pos.line,
+            Line::Col(_) => row_pos.line,
+        };
+        self.enqueue(cross);
+    }
+
+    /// Set a fill that was not deduced from a line and revisit both lines through it
+    ///
+    /// * `puzzle`: Puzzle to set the fill in
+    /// * `pos`: Position of the cell
+    /// * `fill`: Fill to set
+    pub(crate) fn assign_cell(&mut self, puzzle: &mut Puzzle, pos: Position, fill: Fill) {
+        let (row_pos, col_pos) = self.set_cell(puzzle, pos, fill);
+
+        self.enqueue(row_pos.line);
+        self.enqueue(col_pos.line);
+    }
+
+    /// Set the fill of a cell in both the puzzle and the line masks
+    fn set_cell(
+        &mut self,
+        puzzle: &mut Puzzle,
+        pos: Position,
+        fill: Fill,
+    ) -> (LinePosition, LinePosition) {
+        let prev = puzzle[pos];
+        puzzle[pos] = fill;
+
+        let (row_pos, col_pos) = pos.relative();
+        let row_len = puzzle.line_len(row_pos.line) as usize;
+        let col_len = puzzle.line_len(col_pos.line) as usize;
+
+        self.set_mask(row_pos, row_len, prev, fill);
+        self.set_mask(col_pos, col_len, prev, fill);
+
+        (row_pos, col_pos)
+    }
+
+    /// Add a line to the back of the frontier if it is not on it yet
+    pub(crate) fn enqueue(&mut self, line: Line) {
+        if !self.frontier.contains(&line) {
+            self.frontier.push_back(line);
+        }
+    }
+
+    /// Add all lines of the puzzle to the frontier, rows first
+    pub(crate) fn enqueue_all(&mut self, puzzle: &Puzzle) {
+        let rows = (0..puzzle.rows()).map(Line::Row);
+        let cols = (0..puzzle.cols()).map(Line::Col);
+
+        for line in rows.chain(cols) {
+            self.enqueue(line);
+        }
+    }
+
+    /// Rebuild the line masks from the cells that are currently set in the puzzle
+    pub(crate) fn sync_masks(&mut self, puzzle: &Puzzle) {
+        self.masks.clear();
+
+        for row in 0..puzzle.rows() {
+            for col in 0..puzzle.cols() {
+                let pos = Position::new(row, col);
+                let fill = puzzle[pos];
+
+                if matches!(fill, Fill::Blank) {
+                    continue;
+                }
+
+                let (row_pos, col_pos) = pos.relative();
+                self.set_mask(row_pos, puzzle.cols() as usize, Fill::Blank, fill);
+                self.set_mask(col_pos, puzzle.rows() as usize, Fill::Blank, fill);
+            }
+        }
+    }
+
+    /// Validate all lines of the puzzle with a rule
+    pub(crate) fn validate_all(&mut self, puzzle: &Puzzle) {
+        let rows = (0..puzzle.rows()).map(Line::Row);
+        let cols = (0..puzzle.cols()).map(Line::Col);
+
+        for line in rows.chain(cols) {
+            if !self.rules.contains_key(&line) {
+                continue;
+            }
+
+            self.generate_rule_constraints(line);
+
+            let validation = self.validate(puzzle, line);
+            self.validations.insert(line, validation);
+        }
+    }
+
+    /// Mark the line of a contradiction as invalid, as its current cells may still look valid
+    /// Violations that validation found in the line are kept, since they are more precise
+    ///
+    /// * `result`: Result of solving the puzzle
+    pub(crate) fn mark_contradiction<T>(&mut self, result: &Result<T>) {
+        let Err(Error::Solver(SolverError::Contradiction(line))) = result else {
+            return;
+        };
+
+        if self[*line].is_valid() {
+            self.validations.insert(*line, LineValidation::Invalid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, C2, X, solve_picture};
+    use crate::{Rule, Rules};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::plus(3, 3, vec![B, C1, B, C1, C1, C1, B, C1, B])]
+    #[case::full(2, 3, vec![C1; 6])]
+    #[case::empty(2, 2, vec![B; 4])]
+    #[case::multi_color(3, 3, vec![C1, C2, C2, C1, B, C2, C1, C1, C2])]
+    #[case::adjacent_colors(2, 4, vec![C1, C2, C1, C2, C2, C1, C2, C1])]
+    fn solve_line_logic(#[case] rows: u16, #[case] cols: u16, #[case] picture: Vec<Fill>) {
+        let (solved, puzzle) = solve_picture(rows, cols, &picture, Solver::solve_lines);
+        let expected: Vec<_> = picture
+            .iter()
+            .map(|&fill| if fill == B { X } else { fill })
+            .collect();
+
+        assert!(solved.unwrap());
+        assert_eq!(puzzle.iter_cells().copied().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn solve_ambiguous_stalls() {
+        let (solved, puzzle) = solve_picture(2, 2, &[C1, B, B, C1], Solver::solve_lines);
+
+        assert!(!solved.unwrap());
+        assert!(puzzle.iter_cells().all(|&fill| fill == B));
+    }
+
+    #[test]
+    fn solve_contradiction() {
+        let rules = Rules::new(
+            vec![Rule::new(vec![(C1, 2).into()], 2)],
+            vec![Rule::new(vec![], 1), Rule::new(vec![(C1, 1).into()], 1)],
+        );
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+
+        let mut puzzle = Puzzle::empty(1, 2);
+        let solved = solver.solve_lines(&mut puzzle);
+
+        let Err(Error::Solver(SolverError::Contradiction(line))) = solved else {
+            panic!("Expected a contradiction, found {solved:?}");
+        };
+        assert!(!solver[line].is_valid());
+    }
+}
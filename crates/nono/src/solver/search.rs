@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use crate::{
+    Error, Fill, Line, LineConstraint, LineMap, LineMask, LinePosition, Position, Puzzle, Result,
+    Solver, SolverError, SolverEvent,
+};
+
+/// State of the solver and puzzle to return to after a guess
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot {
+    puzzle: Puzzle,
+    masks: LineMap<HashMap<Fill, LineMask>>,
+}
+
+impl Solver {
+    /// Solve the puzzle by propagating line deductions, probing and backtracking on guesses
+    /// Fails with a [`SolverError`] if the puzzle has no solution
+    ///
+    /// * `puzzle`: Puzzle to solve, starting from the cells that are already set
+    pub(crate) fn search(&mut self, puzzle: &mut Puzzle) -> Result<()> {
+        self.propagate(puzzle)?;
+        self.probe(puzzle)?;
+
+        // Every cell is set, so the puzzle is solved
+        let Some((pos, candidates)) = self.choose_cell(puzzle) else {
+            return Ok(());
+        };
+
+        // Otherwise try each of the candidate fills for the most constrained cell
+        self.depth += 1;
+        self.stats.max_depth = self.stats.max_depth.max(self.depth);
+
+        let guessed = self.guess(puzzle, pos, candidates);
+        self.depth -= 1;
+
+        guessed
+    }
+
+    /// Try each of the candidate fills for a cell until one of them leads to a solution
+    ///
+    /// * `puzzle`: Puzzle to guess in
+    /// * `pos`: Position of the cell to guess
+    /// * `candidates`: Fills to try for the cell
+    fn guess(&mut self, puzzle: &mut Puzzle, pos: Position, candidates: Vec<Fill>) -> Result<()> {
+        let snapshot = self.snapshot(puzzle);
+
+        for fill in candidates {
+            tracing::debug!("Guess {fill:?} at {pos}");
+            self.stats.guesses += 1;
+            self.make_guess(puzzle, pos, fill);
+
+            match self.search(puzzle) {
+                Ok(()) => return Ok(()),
+                Err(Error::Solver(err)) if err.is_contradiction() => {
+                    tracing::debug!("Undo guess {fill:?} at {pos}: {err}");
+                    self.undo_guess(puzzle, &snapshot, pos, fill);
+                }
+                Err(err) => {
+                    // Do not leave any guesses behind when the solver is stopped early
+                    self.undo_guess(puzzle, &snapshot, pos, fill);
+                    return Err(err);
+                }
+            }
+        }
+
+        Err(Error::Solver(SolverError::NoSolution))
+    }
+
+    /// Probe each blank cell by trying all of its candidate fills and propagating them
+    /// Cells that are set the same way by every outcome that does not contradict are kept
+    ///
+    /// * `puzzle`: Puzzle to probe, which should have been propagated beforehand
+    pub(crate) fn probe(&mut self, puzzle: &mut Puzzle) -> Result<()> {
+        loop {
+            let mut progress = false;
+            let constraints = self.all_line_constraints(puzzle);
+
+            for pos in blank_cells(puzzle) {
+                // Cell was set by probing an earlier cell
+                if !matches!(puzzle[pos], Fill::Blank) {
+                    continue;
+                }
+
+                self.stats.probes += 1;
+
+                let snapshot = self.snapshot(puzzle);
+                let mut outcomes: Vec<Puzzle> = Vec::new();
+
+                for fill in candidates(&constraints, pos) {
+                    self.make_guess(puzzle, pos, fill);
+
+                    match self.propagate(puzzle) {
+                        Ok(()) => outcomes.push(puzzle.clone()),
+                        Err(Error::Solver(err)) if err.is_contradiction() => {}
+                        Err(err) => {
+                            self.undo_guess(puzzle, &snapshot, pos, fill);
+                            return Err(err);
+                        }
+                    }
+
+                    self.undo_guess(puzzle, &snapshot, pos, fill);
+                }
+
+                let Some((first, rest)) = outcomes.split_first() else {
+                    tracing::debug!("No candidate fill is possible at {pos}");
+                    self.notify(SolverEvent::NoCandidates(pos));
+                    return Err(Error::Solver(SolverError::NoSolution));
+                };
+
+                // Keep all cells that every outcome agrees on
+                for cell in blank_cells(puzzle) {
+                    let fill = first[cell];
+
+                    if !matches!(fill, Fill::Blank) && rest.iter().all(|other| other[cell] == fill)
+                    {
+                        tracing::debug!("Probed {fill:?} at {cell} from {pos}");
+                        self.assign_cell(puzzle, cell, fill);
+                        self.notify(SolverEvent::CellDeduced { pos: cell, fill });
+                        progress = true;
+                    }
+                }
+
+                self.propagate(puzzle)?;
+            }
+
+            if !progress {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Choose the blank cell with the least candidate fills to guess on
+    pub(crate) fn choose_cell(&mut self, puzzle: &Puzzle) -> Option<(Position, Vec<Fill>)> {
+        let constraints = self.all_line_constraints(puzzle);
+
+        blank_cells(puzzle)
+            .into_iter()
+            .map(|pos| (pos, candidates(&constraints, pos)))
+            .min_by_key(|(_, candidates)| candidates.len())
+    }
+
+    /// Find the constraints of all lines given the cells that are currently set
+    fn all_line_constraints(&mut self, puzzle: &Puzzle) -> LineMap<HashMap<Fill, LineConstraint>> {
+        let rows = (0..puzzle.rows()).map(Line::Row);
+        let cols = (0..puzzle.cols()).map(Line::Col);
+
+        rows.chain(cols)
+            .filter_map(|line| {
+                let line_len = puzzle.line_len(line) as usize;
+                let constraints = self.line_constraints(line, line_len).ok()?;
+
+                Some((line, constraints))
+            })
+            .collect()
+    }
+
+    /// Tentatively set a fill, which can be undone by restoring the snapshot taken before
+    ///
+    /// * `puzzle`: Puzzle to guess in
+    /// * `pos`: Position of the cell to guess
+    /// * `fill`: Fill to guess
+    pub(crate) fn make_guess(&mut self, puzzle: &mut Puzzle, pos: Position, fill: Fill) {
+        self.notify(SolverEvent::GuessMade { pos, fill });
+        self.assign_cell(puzzle, pos, fill);
+    }
+
+    /// Undo a guess by restoring the snapshot taken before it was made
+    ///
+    /// * `puzzle`: Puzzle to undo the guess in
+    /// * `snapshot`: Snapshot taken before the guess
+    /// * `pos`: Position of the guessed cell
+    /// * `fill`: Fill that was guessed
+    pub(crate) fn undo_guess(
+        &mut self,
+        puzzle: &mut Puzzle,
+        snapshot: &Snapshot,
+        pos: Position,
+        fill: Fill,
+    ) {
+        self.restore(puzzle, snapshot);
+        self.notify(SolverEvent::GuessUndone { pos, fill });
+    }
+
+    pub(crate) fn snapshot(&self, puzzle: &Puzzle) -> Snapshot {
+        Snapshot {
+            puzzle: puzzle.clone(),
+            masks: self.masks.clone(),
+        }
+    }
+
+    pub(crate) fn restore(&mut self, puzzle: &mut Puzzle, snapshot: &Snapshot) {
+        *puzzle = snapshot.puzzle.clone();
+        self.masks = snapshot.masks.clone();
+        self.frontier.clear();
+    }
+}
+
+/// Find the positions of all cells that are still blank
+fn blank_cells(puzzle: &Puzzle) -> Vec<Position> {
+    let cols = usize::from(puzzle.cols());
+
+    puzzle
+        .iter_cells()
+        .enumerate()
+        .filter(|(_, fill)| matches!(fill, Fill::Blank))
+        .map(|(idx, _)| Position::new((idx / cols) as u16, (idx % cols) as u16))
+        .collect()
+}
+
+/// Find the fills that a cell may take according to both lines through it
+///
+/// * `constraints`: Constraints of all lines
+/// * `pos`: Position of the cell
+fn candidates(constraints: &LineMap<HashMap<Fill, LineConstraint>>, pos: Position) -> Vec<Fill> {
+    let (row_pos, col_pos) = pos.relative();
+
+    let allows = |line_pos: LinePosition, fill: &Fill| {
+        let Some(line) = constraints.get(&line_pos.line) else {
+            return true;
+        };
+
+        line.get(fill)
+            .is_some_and(|constraint| constraint.optional[line_pos.offset as usize])
+    };
+
+    let mut fills: Vec<_> = constraints
+        .get(&row_pos.line)
+        .into_iter()
+        .chain(constraints.get(&col_pos.line))
+        .flat_map(|line| line.keys().copied())
+        .collect();
+
+    fills.sort();
+    fills.dedup();
+    fills.retain(|fill| allows(row_pos, fill) && allows(col_pos, fill));
+
+    fills
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rules;
+    use crate::testing::{B, C1, C2, PROBING, picture, solve_picture};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::diagonal(2, 2, vec![C1, B, B, C1])]
+    #[case::checkers(3, 3, vec![C1, B, C1, B, C1, B, C1, B, C1])]
+    #[case::multi_color(3, 4, vec![C1, B, C2, B, B, C2, B, C1, C1, B, C1, B])]
+    #[case::shifted(4, 4, vec![C1, C1, B, B, B, C1, C1, B, B, B, C1, C1, C1, B, B, C1])]
+    fn solve_with_guesses(#[case] rows: u16, #[case] cols: u16, #[case] fills: Vec<Fill>) {
+        let rules = Rules::from_puzzle(&picture(rows, cols, &fills));
+        let (solved, puzzle) = solve_picture(rows, cols, &fills, Solver::solve);
+        let found = Rules::from_puzzle(&puzzle);
+
+        solved.unwrap();
+        assert!(puzzle.iter_cells().all(|&fill| fill != B));
+        assert_eq!(found.rows, rules.rows);
+        assert_eq!(found.cols, rules.cols);
+    }
+
+    #[test]
+    fn solve_unique_beyond_line_logic() {
+        let rules = Rules::from_puzzle(&picture(4, 4, &PROBING));
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+
+        // Line logic alone gets stuck on the puzzle..
+        let mut puzzle = Puzzle::empty(4, 4);
+        assert!(!solver.solve_lines(&mut puzzle).unwrap());
+
+        // .. but searching finds its only solution
+        let mut puzzle = Puzzle::empty(4, 4);
+        solver.solve(&mut puzzle).unwrap();
+
+        let fills: Vec<_> = puzzle.iter_cells().map(|&fill| fill == C1).collect();
+        let expected: Vec<_> = PROBING.iter().map(|&fill| fill == C1).collect();
+        assert_eq!(fills, expected);
+    }
+}
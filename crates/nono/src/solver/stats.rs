@@ -0,0 +1,18 @@
+/// Work the solver has done since it was last cleared
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SolverStats {
+    /// Number of times all lines on the frontier were worked through
+    pub rounds: usize,
+
+    /// Number of lines that were solved from the frontier
+    pub line_solves: usize,
+
+    /// Number of cells that were probed
+    pub probes: usize,
+
+    /// Number of guesses made while backtracking
+    pub guesses: usize,
+
+    /// Deepest nesting of guesses while backtracking
+    pub max_depth: usize,
+}
@@ -1,4 +1,6 @@
-use crate::{Fill, Line, LineConstraint, LinePosition, Puzzle, Rule, Solver};
+use crate::{
+    Fill, Line, LineConstraint, LinePosition, Puzzle, Rule, RunViolation, Solver, diagnose_line,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub enum LineValidation {
@@ -21,6 +23,9 @@ pub enum LineValidation {
 
     /// Some cells in the line are invalidated by the rule
     Invalid,
+
+    /// Some cells in the line are invalidated by the rule, at a known location
+    Violation(RunViolation),
 }
 
 impl LineValidation {
@@ -35,6 +40,13 @@ impl LineValidation {
     pub fn is_valid(&self) -> bool {
         matches!(self, LineValidation::Valid | LineValidation::Solved)
     }
+
+    pub fn violation(&self) -> Option<&RunViolation> {
+        match self {
+            LineValidation::Violation(violation) => Some(violation),
+            _ => None,
+        }
+    }
 }
 
 impl Solver {
@@ -59,19 +71,28 @@ impl Solver {
         // Then do a quick validation with the rule masks
         let validation = self.validate_masks(line);
         if !validation.is_valid() {
-            return validation;
+            return Self::diagnose(puzzle, rule, line).unwrap_or(validation);
         }
 
         // If still valid, validate with a DP
         let validation = self.validate_dp(puzzle, rule, line);
         if !validation.is_valid() {
-            return validation;
+            return Self::diagnose(puzzle, rule, line).unwrap_or(validation);
         }
 
         // If so, check if it solve the rule
         self.validate_iter(puzzle, rule, line)
     }
 
+    /// Locate the cells and run that make an invalid line violate its rule
+    fn diagnose(puzzle: &Puzzle, rule: &Rule, line: Line) -> Option<LineValidation> {
+        let fills: Vec<_> = puzzle.iter_line(line).copied().collect();
+        let violation = diagnose_line(rule.runs(), &fills)?;
+
+        tracing::info!("Found {violation:?} on {line:?}");
+        Some(LineValidation::Violation(violation))
+    }
+
     fn validate_iter(&self, puzzle: &Puzzle, rule: &Rule, line: Line) -> LineValidation {
         let rule_iter = rule.runs().iter();
         let line_iter = puzzle.iter_runs(line);
@@ -255,3 +276,29 @@ impl Solver {
         LineValidation::Valid
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{B, C1, picture};
+    use crate::{Position, Rules, ViolationKind};
+
+    #[test]
+    fn validate_violation() {
+        let rules = Rules::from_puzzle(&picture(1, 4, &[C1, C1, B, B]));
+
+        let mut solver = Solver::new();
+        solver.insert_rules(&rules);
+
+        let mut puzzle = Puzzle::empty(1, 4);
+        for col in 0..3 {
+            solver.update_cell(&mut puzzle, Position::new(0, col), C1);
+        }
+
+        let violation = solver[Line::Row(0)].violation().copied();
+        let expected = RunViolation::new(ViolationKind::RunTooLong, 0..3, Some(0));
+
+        assert_eq!(violation, Some(expected));
+        assert!(solver[Line::Col(1)].is_valid());
+    }
+}
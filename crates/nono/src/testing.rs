@@ -0,0 +1,53 @@
+use crate::{Fill, Metadata, Nonogram, Puzzle, Rules, Solver};
+
+pub const B: Fill = Fill::Blank;
+pub const X: Fill = Fill::Cross;
+pub const C1: Fill = Fill::Color(1);
+pub const C2: Fill = Fill::Color(2);
+pub const C3: Fill = Fill::Color(3);
+
+/// Picture that is unique, but needs probing on top of line logic to be solved
+pub const PROBING: [Fill; 16] = [
+    C1, C1, B, B, //
+    B, C1, B, C1, //
+    B, B, C1, B, //
+    C1, B, B, C1,
+];
+
+/// Puzzle with the fills of a picture, row by row
+pub fn picture(rows: u16, cols: u16, fills: &[Fill]) -> Puzzle {
+    Puzzle::new(rows, cols, fills.to_vec()).unwrap()
+}
+
+/// Nonogram with the rules of a picture and a puzzle that is not started yet
+pub fn picture_nonogram(rows: u16, cols: u16, fills: &[Fill]) -> Nonogram {
+    Nonogram {
+        rules: Rules::from_puzzle(&picture(rows, cols, fills)),
+        puzzle: Puzzle::empty(rows, cols),
+        colors: vec![(0, 0, 0)],
+        metadata: Metadata::default(),
+    }
+}
+
+/// Solve an empty puzzle with the rules of a picture
+/// Returns the result of the solve along with the puzzle it left behind
+///
+/// * `rows`, `cols`: Size of the picture
+/// * `fills`: Fills of the picture, row by row
+/// * `solve`: How to solve the puzzle with a solver that has the rules inserted
+pub fn solve_picture<T>(
+    rows: u16,
+    cols: u16,
+    fills: &[Fill],
+    solve: impl FnOnce(&mut Solver, &mut Puzzle) -> T,
+) -> (T, Puzzle) {
+    let rules = Rules::from_puzzle(&picture(rows, cols, fills));
+
+    let mut solver = Solver::new();
+    solver.insert_rules(&rules);
+
+    let mut puzzle = Puzzle::empty(rows, cols);
+    let solved = solve(&mut solver, &mut puzzle);
+
+    (solved, puzzle)
+}